@@ -0,0 +1,16 @@
+import asyncio
+from spider_rs import Website
+
+async def heartbeat() -> None:
+    while True:
+        print("event loop is free")
+        await asyncio.sleep(1)
+
+async def main() -> None:
+    website: Website = Website("https://choosealicense.com")
+    ticker = asyncio.create_task(heartbeat())
+    await website.crawl_async()
+    ticker.cancel()
+    print(website.get_links())
+
+asyncio.run(main())
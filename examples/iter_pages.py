@@ -0,0 +1,8 @@
+from spider_rs import Website
+
+def main() -> None:
+    website: Website = Website("https://choosealicense.com")
+    for page in website.iter_pages("crawl"):
+        print(f"{page.url} - status: {page.status_code}")
+
+main()
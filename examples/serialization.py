@@ -0,0 +1,25 @@
+import pickle
+from spider_rs import NPage
+
+raw = b"<html>\xff\x00\x80</html>"
+image = b"\x89PNG\r\n\x1a\n\x00\xff"
+
+page = NPage.from_dict({
+    "url": "https://choosealicense.com",
+    "status_code": 200,
+    "raw_content": raw,
+    "screenshot": {"format": "png", "data": image},
+    "links": ["https://choosealicense.com/licenses/"],
+})
+
+for restored in [
+    NPage.from_dict(page.to_dict()),
+    NPage.from_json(page.to_json()),
+    pickle.loads(pickle.dumps(page)),
+]:
+    assert restored.url == page.url
+    assert restored.links == page.links
+    assert restored.raw_content == raw
+    assert restored.screenshot.data == image
+
+print("round trip ok")
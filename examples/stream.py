@@ -0,0 +1,9 @@
+import asyncio
+from spider_rs import Website
+
+async def main() -> None:
+    website: Website = Website("https://choosealicense.com")
+    async for page in website.stream():
+        print(f"{page.url} - status: {page.status_code}")
+
+asyncio.run(main())
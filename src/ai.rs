@@ -0,0 +1,104 @@
+use pyo3::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// the output of an OpenAI prompt ran on the page.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+#[pyclass]
+pub struct AIResult {
+  #[pyo3(get)]
+  /// The prompt sent.
+  pub input: String,
+  #[pyo3(get)]
+  /// The javascript generated to run on the page.
+  pub js_output: String,
+  #[pyo3(get)]
+  /// The content extracted from the page. This is JSON when the prompt asked for extraction.
+  pub content_output: Vec<String>,
+  #[pyo3(get)]
+  /// The error of the prompt.
+  pub error: Option<String>,
+}
+
+#[pymethods]
+impl AIResult {
+  fn __repr__(&self) -> String {
+    format!(
+      "AIResult(input={:?}, js_output={:?}, error={:?})",
+      self.input, self.js_output, self.error
+    )
+  }
+}
+
+impl From<&spider::page::AIResults> for AIResult {
+  fn from(result: &spider::page::AIResults) -> Self {
+    AIResult {
+      input: result.input.clone(),
+      js_output: result.js_output.clone(),
+      content_output: result.content_output.clone(),
+      error: result.error.clone(),
+    }
+  }
+}
+
+/// the OpenAI tokens used.
+#[derive(Debug, Default, Clone, Copy, Serialize, Deserialize)]
+#[serde(default)]
+#[pyclass]
+pub struct AIUsage {
+  #[pyo3(get)]
+  /// The tokens used by the prompts.
+  pub prompt_tokens: u64,
+  #[pyo3(get)]
+  /// The tokens used by the completions.
+  pub completion_tokens: u64,
+  #[pyo3(get)]
+  /// The total tokens used.
+  pub total_tokens: u64,
+  #[pyo3(get)]
+  /// The amount of requests sent.
+  pub requests: u64,
+}
+
+#[pymethods]
+impl AIUsage {
+  fn __repr__(&self) -> String {
+    format!(
+      "AIUsage(prompt_tokens={}, completion_tokens={}, total_tokens={}, requests={})",
+      self.prompt_tokens, self.completion_tokens, self.total_tokens, self.requests
+    )
+  }
+}
+
+impl AIUsage {
+  /// add the usage of another run.
+  pub fn add(&mut self, usage: &AIUsage) {
+    self.prompt_tokens += usage.prompt_tokens;
+    self.completion_tokens += usage.completion_tokens;
+    self.total_tokens += usage.total_tokens;
+    self.requests += usage.requests;
+  }
+
+  /// the usage of the prompts ran on the page.
+  pub fn from_page(res: &spider::page::Page) -> Option<Self> {
+    let credits = res.openai_credits_used.as_ref()?;
+    let mut usage = AIUsage::default();
+
+    for credit in credits.iter() {
+      usage.prompt_tokens += credit.prompt_tokens as u64;
+      usage.completion_tokens += credit.completion_tokens as u64;
+      usage.total_tokens += credit.total_tokens as u64;
+      usage.requests += 1;
+    }
+
+    Some(usage)
+  }
+}
+
+/// the results of the prompts ran on the page.
+pub fn ai_results(res: &spider::page::Page) -> Option<Vec<AIResult>> {
+  res
+    .extra_ai_data
+    .as_ref()
+    .map(|results| results.iter().map(AIResult::from).collect())
+}
@@ -0,0 +1,114 @@
+use pyo3::prelude::*;
+use std::sync::{Arc, Mutex};
+
+/// how to handle exceptions raised by python page callbacks.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorPolicy {
+  /// drop the exception and keep crawling.
+  #[default]
+  Ignore,
+  /// print the traceback and keep crawling.
+  Log,
+  /// store the exception on the website and keep crawling.
+  Collect,
+  /// store the exception, stop the crawl, and raise it from the crawl call.
+  Raise,
+}
+
+impl ErrorPolicy {
+  /// parse the error policy from python.
+  pub fn parse(policy: &str) -> PyResult<Self> {
+    match policy {
+      "ignore" => Ok(ErrorPolicy::Ignore),
+      "log" => Ok(ErrorPolicy::Log),
+      "collect" => Ok(ErrorPolicy::Collect),
+      "raise" => Ok(ErrorPolicy::Raise),
+      p => Err(pyo3::exceptions::PyValueError::new_err(format!(
+        "Unknown callback error policy {p}, expected ignore, log, collect, or raise"
+      ))),
+    }
+  }
+}
+
+/// the callback error policy with the exceptions collected.
+#[derive(Debug, Default, Clone)]
+pub struct CallbackErrors {
+  /// the policy to apply.
+  pub policy: ErrorPolicy,
+  /// the exceptions stored shared across the crawl tasks.
+  errors: Arc<Mutex<Vec<PyErr>>>,
+}
+
+impl CallbackErrors {
+  /// apply the policy to the result of a callback. Returns false when the crawl should stop.
+  pub fn handle(&self, py: Python<'_>, result: PyResult<PyObject>) -> bool {
+    match result {
+      Ok(_) => true,
+      Err(e) => match self.policy {
+        ErrorPolicy::Ignore => true,
+        ErrorPolicy::Log => {
+          e.print(py);
+          true
+        }
+        ErrorPolicy::Collect => {
+          self.push(e);
+          true
+        }
+        ErrorPolicy::Raise => {
+          self.push(e);
+          false
+        }
+      },
+    }
+  }
+
+  /// store an exception.
+  fn push(&self, e: PyErr) {
+    if let Ok(mut errors) = self.errors.lock() {
+      errors.push(e);
+    }
+  }
+
+  /// the amount of exceptions stored.
+  pub fn len(&self) -> usize {
+    match self.errors.lock() {
+      Ok(errors) => errors.len(),
+      _ => 0,
+    }
+  }
+
+  /// is the exception store empty.
+  pub fn is_empty(&self) -> bool {
+    self.len() == 0
+  }
+
+  /// the exception to raise from the crawl if any were stored after the index using the raise policy.
+  pub fn raised_since(&self, py: Python<'_>, start: usize) -> PyResult<()> {
+    if self.policy == ErrorPolicy::Raise {
+      if let Ok(errors) = self.errors.lock() {
+        if let Some(e) = errors.get(start) {
+          return Err(e.clone_ref(py));
+        }
+      }
+    }
+    Ok(())
+  }
+
+  /// all of the exceptions stored.
+  pub fn to_list(&self, py: Python<'_>) -> Vec<PyObject> {
+    match self.errors.lock() {
+      Ok(errors) => errors
+        .iter()
+        .map(|e| e.clone_ref(py).into_value(py).into_any())
+        .collect(),
+      _ => Default::default(),
+    }
+  }
+
+  /// remove all of the exceptions stored.
+  pub fn clear(&self) {
+    if let Ok(mut errors) = self.errors.lock() {
+      errors.clear();
+    }
+  }
+}
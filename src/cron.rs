@@ -0,0 +1,246 @@
+use crate::ai::AIUsage;
+use crate::npage::PageOptions;
+use crate::website::{add_ai_usage, CrawlMode};
+use crate::CrawlReport;
+use chrono::Utc;
+use cron::Schedule;
+use pyo3::exceptions::{PyRuntimeError, PyValueError};
+use pyo3::prelude::*;
+use spider::tokio::sync::watch;
+use spider::tokio::task::JoinHandle;
+use spider::tokio::{pin, select};
+use spider::utils::shutdown;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+
+/// the state of the cron shared with the scheduler.
+#[derive(Default)]
+struct CronState {
+  /// the next scheduled run as seconds since the unix epoch.
+  next_run: Option<f64>,
+  /// the links found on the last run.
+  last_links: Vec<String>,
+  /// the amount of pages crawled on the last run.
+  last_size: u32,
+  /// the report of the last run.
+  last_report: Option<CrawlReport>,
+}
+
+/// a runner for handling crons
+#[pyclass]
+pub struct Cron {
+  /// the scheduler task
+  inner: JoinHandle<()>,
+  /// stop the scheduler.
+  stopped: watch::Sender<bool>,
+  /// pause the scheduler.
+  paused: watch::Sender<bool>,
+  /// the amount of runs completed.
+  runs: watch::Receiver<u32>,
+  /// the state of the runs.
+  state: Arc<Mutex<CronState>>,
+}
+
+impl Cron {
+  /// start the cron scheduler for the website using the configured cron_str and cron_type.
+  /// The OpenAI tokens used by each run are added to the website usage.
+  pub fn spawn(
+    mut website: spider::website::Website,
+    cron_handle: Option<JoinHandle<()>>,
+    ai_usage: Arc<Mutex<AIUsage>>,
+    page_options: PageOptions,
+  ) -> PyResult<Self> {
+    let schedule = Schedule::from_str(website.configuration.cron_str.trim())
+      .map_err(|e| PyValueError::new_err(format!("Invalid cron expression: {e}")))?;
+    let mode = match website.configuration.cron_type {
+      spider::website::CronType::Scrape => CrawlMode::Scrape,
+      _ => CrawlMode::Crawl,
+    };
+    let (stopped, mut stopped_rx) = watch::channel(false);
+    let (paused, mut paused_rx) = watch::channel(false);
+    let domain_name = website.get_url().inner().clone();
+    let (runs_tx, runs) = watch::channel(0);
+    let state = Arc::new(Mutex::new(CronState::default()));
+    let task_state = state.clone();
+
+    let inner = pyo3_async_runtimes::tokio::get_runtime().spawn(async move {
+      loop {
+        select! {
+          resumed = wait_paused(&mut paused_rx, false) => if !resumed {
+            break;
+          },
+          _ = wait_stopped(&mut stopped_rx) => break,
+        }
+
+        let next = match schedule.upcoming(Utc).next() {
+          Some(next) => next,
+          _ => break,
+        };
+
+        set_next_run(&task_state, Some(next.timestamp_millis() as f64 / 1000.0));
+
+        let delay = (next - Utc::now()).to_std().unwrap_or_default();
+
+        select! {
+          _ = spider::tokio::time::sleep(delay) => (),
+          _ = wait_paused(&mut paused_rx, true) => {
+            set_next_run(&task_state, None);
+            continue;
+          }
+          _ = wait_stopped(&mut stopped_rx) => break,
+        }
+
+        website.clear();
+
+        let (report, stopping) = {
+          // the cron runs use the chrome crawl when the feature is enabled.
+          let run = mode.run_with_report(&mut website, true, &page_options);
+          pin!(run);
+
+          select! {
+            report = &mut run => (report, false),
+            _ = wait_stopped(&mut stopped_rx) => {
+              // shut the crawl down gracefully keeping the report of the pages found.
+              shutdown(&domain_name).await;
+              (run.await, true)
+            }
+          }
+        };
+
+        add_ai_usage(&ai_usage, &report);
+
+        if let Ok(mut state) = task_state.lock() {
+          state.last_links = website
+            .get_links()
+            .iter()
+            .map(|x| x.as_ref().to_string())
+            .collect();
+          state.last_size = website.size() as u32;
+          state.last_report = Some(report);
+        }
+
+        runs_tx.send_modify(|runs| *runs += 1);
+
+        if stopping {
+          break;
+        }
+      }
+
+      set_next_run(&task_state, None);
+
+      // end the page callbacks once the last run completes.
+      if let Some(cron_handle) = cron_handle {
+        cron_handle.abort();
+      }
+    });
+
+    Ok(Cron {
+      inner,
+      stopped,
+      paused,
+      runs,
+      state,
+    })
+  }
+}
+
+/// wait until the paused state matches. Returns false if the cron was dropped.
+async fn wait_paused(paused: &mut watch::Receiver<bool>, state: bool) -> bool {
+  paused.wait_for(|p| *p == state).await.is_ok()
+}
+
+/// wait until the cron is stopped.
+async fn wait_stopped(stopped: &mut watch::Receiver<bool>) {
+  let _ = stopped.wait_for(|s| *s).await;
+}
+
+/// set the next scheduled run.
+fn set_next_run(state: &Mutex<CronState>, next_run: Option<f64>) {
+  if let Ok(mut state) = state.lock() {
+    state.next_run = next_run;
+  }
+}
+
+#[pymethods]
+impl Cron {
+  /// stop the cron instance. A run in progress is shut down gracefully and its report is kept.
+  pub fn stop(&self) {
+    self.stopped.send_replace(true);
+    set_next_run(&self.state, None);
+  }
+
+  /// pause the scheduled runs. A run in progress completes.
+  pub fn pause(&self) {
+    self.paused.send_replace(true);
+  }
+
+  /// resume the scheduled runs.
+  pub fn resume(&self) {
+    self.paused.send_replace(false);
+  }
+
+  /// is the cron paused.
+  pub fn is_paused(&self) -> bool {
+    *self.paused.borrow()
+  }
+
+  /// is the cron scheduler running.
+  pub fn is_running(&self) -> bool {
+    !self.inner.is_finished()
+  }
+
+  /// the next scheduled run as seconds since the unix epoch. None when paused or stopped.
+  #[getter]
+  pub fn next_run(&self) -> Option<f64> {
+    match self.state.lock() {
+      Ok(state) => state.next_run,
+      _ => None,
+    }
+  }
+
+  /// the amount of runs completed.
+  #[getter]
+  pub fn run_count(&self) -> u32 {
+    *self.runs.borrow()
+  }
+
+  /// the links found on the last run.
+  #[getter]
+  pub fn last_links(&self) -> Vec<String> {
+    match self.state.lock() {
+      Ok(state) => state.last_links.clone(),
+      _ => Default::default(),
+    }
+  }
+
+  /// the amount of pages crawled on the last run.
+  #[getter]
+  pub fn last_size(&self) -> u32 {
+    match self.state.lock() {
+      Ok(state) => state.last_size,
+      _ => 0,
+    }
+  }
+
+  /// the report of the last run.
+  #[getter]
+  pub fn last_report(&self) -> Option<CrawlReport> {
+    match self.state.lock() {
+      Ok(state) => state.last_report.clone(),
+      _ => None,
+    }
+  }
+
+  /// an awaitable that resolves with the run count when the next run completes.
+  pub fn wait_next_run<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+    let mut runs = self.runs.clone();
+    let _ = runs.borrow_and_update();
+
+    pyo3_async_runtimes::tokio::future_into_py(py, async move {
+      match runs.changed().await {
+        Ok(_) => Ok(*runs.borrow()),
+        _ => Err(PyRuntimeError::new_err("The cron is stopped")),
+      }
+    })
+  }
+}
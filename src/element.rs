@@ -0,0 +1,88 @@
+use ego_tree::NodeId;
+use pyo3::prelude::*;
+use scraper::{ElementRef, Html};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// an element matched from a css selector. The text and html are read from the page document when requested.
+#[derive(Debug, Clone)]
+#[pyclass]
+pub struct Element {
+  #[pyo3(get)]
+  /// The tag name of the element.
+  pub tag: String,
+  /// The parsed document of the page holding the element.
+  document: Arc<Mutex<Option<Html>>>,
+  /// The element in the document tree.
+  node: NodeId,
+}
+
+impl Element {
+  /// a new element of the page document.
+  pub fn new(element: ElementRef<'_>, document: &Arc<Mutex<Option<Html>>>) -> Self {
+    Element {
+      tag: element.value().name().to_string(),
+      document: document.clone(),
+      node: element.id(),
+    }
+  }
+
+  /// use the element in the page document.
+  fn with_element<R: Default>(&self, f: impl FnOnce(ElementRef<'_>) -> R) -> R {
+    let document = match self.document.lock() {
+      Ok(document) => document,
+      Err(e) => e.into_inner(),
+    };
+
+    match document
+      .as_ref()
+      .and_then(|document| document.tree.get(self.node))
+      .and_then(ElementRef::wrap)
+    {
+      Some(element) => f(element),
+      _ => Default::default(),
+    }
+  }
+}
+
+#[pymethods]
+impl Element {
+  /// get an attribute of the element.
+  pub fn attr(&self, name: &str) -> Option<String> {
+    self.with_element(|element| element.value().attr(name).map(|v| v.to_string()))
+  }
+
+  /// The text of the element and its descendants.
+  #[getter]
+  pub fn text(&self) -> String {
+    self.with_element(|element| element.text().collect())
+  }
+
+  /// The html inside the element.
+  #[getter]
+  pub fn inner_html(&self) -> String {
+    self.with_element(|element| element.inner_html())
+  }
+
+  /// The html of the element including the element.
+  #[getter]
+  pub fn outer_html(&self) -> String {
+    self.with_element(|element| element.html())
+  }
+
+  /// The attributes of the element.
+  #[getter]
+  pub fn attributes(&self) -> HashMap<String, String> {
+    self.with_element(|element| {
+      element
+        .value()
+        .attrs()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect()
+    })
+  }
+
+  fn __repr__(&self) -> String {
+    format!("Element(tag={:?}, text={:?})", self.tag, self.text())
+  }
+}
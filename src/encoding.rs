@@ -0,0 +1,165 @@
+use encoding_rs::{Encoding, UTF_8};
+
+/// the bytes searched for the meta charset.
+const META_SNIFF_LIMIT: usize = 1024;
+
+/// get the charset parameter of the content type.
+fn content_type_charset(content_type: &str) -> Option<&'static Encoding> {
+  content_type.split(';').skip(1).find_map(|param| {
+    let (name, value) = param.split_once('=')?;
+
+    if name.trim().eq_ignore_ascii_case("charset") {
+      Encoding::for_label(value.trim().trim_matches(['"', '\'']).as_bytes())
+    } else {
+      None
+    }
+  })
+}
+
+/// get the charset declared in the html meta tags.
+fn meta_charset(bytes: &[u8]) -> Option<&'static Encoding> {
+  let head = &bytes[..bytes.len().min(META_SNIFF_LIMIT)];
+  let head = String::from_utf8_lossy(head).to_ascii_lowercase();
+  let mut rest = head.as_str();
+
+  while let Some(start) = rest.find("<meta") {
+    rest = &rest[start + 5..];
+    let tag = &rest[..rest.find('>').unwrap_or(rest.len())];
+
+    if let Some(position) = tag.find("charset=") {
+      let value = tag[position + 8..].trim_start_matches(['"', '\'', ' ']);
+      let end = value
+        .find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | ':' | '.')))
+        .unwrap_or(value.len());
+
+      if let Some(encoding) = Encoding::for_label(&value.as_bytes()[..end]) {
+        // the meta charset can not switch to UTF-16 since the document was read as ascii.
+        return Some(
+          if encoding == encoding_rs::UTF_16LE || encoding == encoding_rs::UTF_16BE {
+            UTF_8
+          } else {
+            encoding
+          },
+        );
+      }
+    }
+  }
+
+  None
+}
+
+/// detect the encoding of the bytes from the BOM, the content type charset, or the meta charset. Defaults to UTF-8.
+/// Non ascii bytes that are valid UTF-8 are read as UTF-8 since chrome serializes the rendered page as UTF-8 keeping the declared charset.
+pub fn detect(bytes: &[u8], content_type: Option<&str>) -> &'static str {
+  let encoding = match Encoding::for_bom(bytes) {
+    Some((encoding, _)) => encoding,
+    _ => match content_type
+      .and_then(content_type_charset)
+      .or_else(|| meta_charset(bytes))
+    {
+      Some(_) if !bytes.is_ascii() && std::str::from_utf8(bytes).is_ok() => UTF_8,
+      Some(encoding) => encoding,
+      _ => UTF_8,
+    },
+  };
+
+  encoding.name()
+}
+
+/// decode the bytes with the encoding label skipping the BOM. Defaults to UTF-8.
+pub fn decode(bytes: &[u8], label: &str) -> String {
+  let encoding = Encoding::for_label(label.as_bytes()).unwrap_or(UTF_8);
+  let bom_length = Encoding::for_bom(bytes).map_or(0, |(_, length)| length);
+  let (content, _) = encoding.decode_without_bom_handling(&bytes[bom_length..]);
+
+  content.into_owned()
+}
+
+/// detect the encoding of the page content.
+pub fn detect_page(res: &spider::page::Page) -> &'static str {
+  let content_type = res
+    .headers
+    .as_ref()
+    .and_then(|headers| headers.get(spider::reqwest::header::CONTENT_TYPE))
+    .and_then(|value| value.to_str().ok());
+
+  detect(res.get_html_bytes_u8(), content_type)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn bom() {
+    assert_eq!(
+      detect(
+        b"\xEF\xBB\xBFhello",
+        Some("text/html; charset=windows-1252")
+      ),
+      "UTF-8"
+    );
+    assert_eq!(detect(b"\xFF\xFEh\x00", None), "UTF-16LE");
+    assert_eq!(decode(b"\xEF\xBB\xBFhello", "UTF-8"), "hello");
+  }
+
+  #[test]
+  fn content_type() {
+    assert_eq!(
+      detect(b"caf\xE9", Some("text/html; charset=\"Shift_JIS\"")),
+      "Shift_JIS"
+    );
+    assert_eq!(
+      detect(b"caf\xE9", Some("text/html;charset='latin1'")),
+      "windows-1252"
+    );
+    assert_eq!(detect(b"hello", Some("text/html")), "UTF-8");
+  }
+
+  #[test]
+  fn meta() {
+    assert_eq!(
+      detect(b"<meta charset=\"windows-1252\"><p>caf\xE9", None),
+      "windows-1252"
+    );
+    assert_eq!(
+      detect(
+        b"<meta http-equiv=\"Content-Type\" content=\"text/html; charset=shift_jis\">\x82\xA0",
+        None
+      ),
+      "Shift_JIS"
+    );
+    assert_eq!(detect(b"<meta charset=\"utf-16\">hello", None), "UTF-8");
+  }
+
+  #[test]
+  fn content_type_before_meta() {
+    assert_eq!(
+      detect(
+        b"<meta charset=\"shift_jis\">caf\xE9",
+        Some("text/html; charset=windows-1252")
+      ),
+      "windows-1252"
+    );
+  }
+
+  #[test]
+  fn valid_utf8_over_declared_charset() {
+    let rendered = "<meta charset=\"shift_jis\"><p>\u{3053}\u{3093}\u{306B}\u{3061}\u{306F}</p>";
+
+    assert_eq!(
+      detect(rendered.as_bytes(), Some("text/html; charset=Shift_JIS")),
+      "UTF-8"
+    );
+    assert_eq!(
+      detect(b"<meta charset=\"shift_jis\">hello", None),
+      "Shift_JIS"
+    );
+  }
+
+  #[test]
+  fn decodes_the_detected_encoding() {
+    assert_eq!(decode(b"caf\xE9", "windows-1252"), "caf\u{e9}");
+    assert_eq!(decode(b"\x82\xA0", "Shift_JIS"), "\u{3042}");
+  }
+}
@@ -0,0 +1,156 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::PyResult;
+use scraper::{ElementRef, Html, Selector};
+use serde_json::{Map, Value as JsonValue};
+
+/// a field of the extraction schema.
+#[derive(Debug)]
+struct ExtractionField {
+  /// the name of the field.
+  name: String,
+  /// the css selector of the field.
+  selector: Selector,
+  /// the attribute to read instead of the text.
+  attr: Option<String>,
+  /// collect all of the matches as a list.
+  multiple: bool,
+  /// the nested fields evaluated inside each match.
+  fields: Option<Vec<ExtractionField>>,
+}
+
+/// the schema of fields extracted from each page with css selectors.
+///
+/// Each field maps to a selector string or an object with the `selector`, an optional `attr` to read,
+/// `multiple` to return every match, and nested `fields` evaluated inside each match.
+#[derive(Debug, Default)]
+pub struct ExtractionSchema {
+  /// the fields to extract.
+  fields: Vec<ExtractionField>,
+}
+
+impl ExtractionSchema {
+  /// parse the schema from the json config.
+  pub fn parse(schema: &JsonValue) -> PyResult<Self> {
+    Ok(ExtractionSchema {
+      fields: parse_fields(schema, "")?,
+    })
+  }
+
+  /// extract the fields from the document.
+  pub fn extract(&self, document: &Html) -> JsonValue {
+    extract_fields(&self.fields, document.root_element())
+  }
+}
+
+/// parse the fields of a schema object.
+fn parse_fields(schema: &JsonValue, path: &str) -> PyResult<Vec<ExtractionField>> {
+  let schema = match schema.as_object() {
+    Some(schema) => schema,
+    _ => {
+      return Err(PyValueError::new_err(format!(
+        "The extraction schema {path} must be a dict of fields"
+      )))
+    }
+  };
+
+  schema
+    .iter()
+    .map(|(name, field)| {
+      let path = if path.is_empty() {
+        name.clone()
+      } else {
+        format!("{path}.{name}")
+      };
+      parse_field(name, field, &path)
+    })
+    .collect()
+}
+
+/// parse a single field of the schema.
+fn parse_field(name: &str, field: &JsonValue, path: &str) -> PyResult<ExtractionField> {
+  let (selector, attr, multiple, fields) = match field {
+    JsonValue::String(selector) => (selector.as_str(), None, false, None),
+    JsonValue::Object(config) => {
+      let selector = match config.get("selector").and_then(|s| s.as_str()) {
+        Some(selector) => selector,
+        _ => {
+          return Err(PyValueError::new_err(format!(
+            "The extraction field {path} requires a selector"
+          )))
+        }
+      };
+      let attr = config
+        .get("attr")
+        .and_then(|a| a.as_str())
+        .map(|a| a.to_string());
+      let multiple = config
+        .get("multiple")
+        .and_then(|m| m.as_bool())
+        .unwrap_or_default();
+      let fields = match config.get("fields") {
+        Some(fields) if !fields.is_null() => Some(parse_fields(fields, path)?),
+        _ => None,
+      };
+
+      (selector, attr, multiple, fields)
+    }
+    _ => {
+      return Err(PyValueError::new_err(format!(
+        "The extraction field {path} must be a selector or a dict"
+      )))
+    }
+  };
+
+  let selector = Selector::parse(selector).map_err(|e| {
+    PyValueError::new_err(format!(
+      "Invalid css selector {selector} for the extraction field {path}: {e}"
+    ))
+  })?;
+
+  Ok(ExtractionField {
+    name: name.to_string(),
+    selector,
+    attr,
+    multiple,
+    fields,
+  })
+}
+
+/// extract the fields inside the element.
+fn extract_fields(fields: &[ExtractionField], element: ElementRef<'_>) -> JsonValue {
+  let mut map = Map::new();
+
+  for field in fields {
+    let value = if field.multiple {
+      JsonValue::Array(
+        element
+          .select(&field.selector)
+          .map(|e| extract_value(field, e))
+          .collect(),
+      )
+    } else {
+      match element.select(&field.selector).next() {
+        Some(e) => extract_value(field, e),
+        _ => JsonValue::Null,
+      }
+    };
+
+    map.insert(field.name.clone(), value);
+  }
+
+  JsonValue::Object(map)
+}
+
+/// extract the value of a matched element.
+fn extract_value(field: &ExtractionField, element: ElementRef<'_>) -> JsonValue {
+  match field.fields {
+    Some(ref fields) => extract_fields(fields, element),
+    _ => match field.attr {
+      Some(ref attr) => match element.value().attr(attr) {
+        Some(value) => JsonValue::String(value.to_string()),
+        _ => JsonValue::Null,
+      },
+      _ => JsonValue::String(element.text().collect::<String>().trim().to_string()),
+    },
+  }
+}
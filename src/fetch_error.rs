@@ -0,0 +1,101 @@
+use pyo3::prelude::*;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use spider::lazy_static::lazy_static;
+
+lazy_static! {
+  /// the urls included in the error messages.
+  static ref URL: Regex = Regex::new(r#"(?i)[a-z][a-z0-9+.-]*://[^\s"')]*"#).unwrap();
+}
+
+/// the error of a page that failed to fetch. The links blocked by robots.txt are never fetched so no page is sent for them,
+/// they are listed on the CrawlReport robots_blocked_urls.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[pyclass]
+pub struct FetchError {
+  #[pyo3(get)]
+  /// The kind of error: dns, connect, connect_timeout, tls, request_timeout, body_too_large, chrome_navigation, http, or other.
+  pub kind: String,
+  #[pyo3(get)]
+  /// The error message.
+  pub message: String,
+}
+
+#[pymethods]
+impl FetchError {
+  fn __repr__(&self) -> String {
+    format!(
+      "FetchError(kind={:?}, message={:?})",
+      self.kind, self.message
+    )
+  }
+}
+
+/// classify the error message of the page. The urls are removed so the host or path can not match a kind.
+fn classify(message: &str) -> &'static str {
+  let message = URL.replace_all(message, "").to_ascii_lowercase();
+  let has = |patterns: &[&str]| patterns.iter().any(|p| message.contains(p));
+  let connecting = has(&[
+    "tcp connect error",
+    "error trying to connect",
+    "connecterror",
+  ]);
+
+  if has(&["net::err_"]) {
+    "chrome_navigation"
+  } else if has(&[
+    "dns error",
+    "failed to lookup address",
+    "name or service not known",
+    "no such host is known",
+    "nodename nor servname provided",
+  ]) {
+    "dns"
+  } else if has(&[
+    "invalid peer certificate",
+    "certificate verify failed",
+    "invalid certificate",
+    "tls handshake",
+    "handshake failure",
+  ]) {
+    "tls"
+  } else if has(&["length limit exceeded", "body limit exceeded"]) {
+    "body_too_large"
+  } else if connecting && has(&["timed out"]) {
+    "connect_timeout"
+  } else if has(&["operation timed out", "request timed out", "timedout"]) {
+    "request_timeout"
+  } else if connecting || has(&["connection refused", "connection reset"]) {
+    "connect"
+  } else {
+    "other"
+  }
+}
+
+/// get the error of the page from the error state or the status code.
+pub fn fetch_error(res: &spider::page::Page) -> Option<FetchError> {
+  let status_code = res.status_code;
+
+  match res.error_status {
+    Some(ref message) => Some(FetchError {
+      kind: classify(message).into(),
+      message: message.clone(),
+    }),
+    _ if status_code.is_client_error() || status_code.is_server_error() => {
+      let kind = match status_code.as_u16() {
+        408 | 504 | 524 => "request_timeout",
+        413 => "body_too_large",
+        _ => "http",
+      };
+
+      Some(FetchError {
+        kind: kind.into(),
+        message: status_code
+          .canonical_reason()
+          .unwrap_or("request failed")
+          .to_string(),
+      })
+    }
+    _ => None,
+  }
+}
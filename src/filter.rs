@@ -0,0 +1,92 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::PyResult;
+use regex::Regex;
+use spider::reqwest::header::CONTENT_TYPE;
+use std::collections::HashSet;
+
+/// filters evaluated on the pages before they are converted for python.
+#[derive(Debug, Default, Clone)]
+pub struct PageFilter {
+  /// the HTTP status codes to allow.
+  status_codes: Option<HashSet<u16>>,
+  /// the url pattern to match.
+  url_regex: Option<Regex>,
+  /// the content type prefixes to allow ex: text/html.
+  content_types: Option<Vec<String>>,
+  /// the minimum size of the page in bytes.
+  min_bytes: Option<usize>,
+}
+
+impl PageFilter {
+  /// a new page filter.
+  pub fn new(
+    status_codes: Option<Vec<u16>>,
+    url_regex: Option<String>,
+    content_types: Option<Vec<String>>,
+    min_bytes: Option<usize>,
+  ) -> PyResult<Self> {
+    let url_regex = match url_regex {
+      Some(pattern) => Some(
+        Regex::new(&pattern)
+          .map_err(|e| PyValueError::new_err(format!("Invalid url_regex: {e}")))?,
+      ),
+      _ => None,
+    };
+
+    Ok(PageFilter {
+      status_codes: status_codes.map(|codes| codes.into_iter().collect()),
+      url_regex,
+      content_types: content_types.map(|types| {
+        types
+          .into_iter()
+          .map(|t| t.trim().to_ascii_lowercase())
+          .collect()
+      }),
+      min_bytes,
+    })
+  }
+
+  /// does the page pass all of the filters set.
+  pub fn matches(&self, page: &spider::page::Page) -> bool {
+    if let Some(ref status_codes) = self.status_codes {
+      if !status_codes.contains(&page.status_code.as_u16()) {
+        return false;
+      }
+    }
+
+    if let Some(min_bytes) = self.min_bytes {
+      if page.get_html_bytes_u8().len() < min_bytes {
+        return false;
+      }
+    }
+
+    if let Some(ref url_regex) = self.url_regex {
+      if !url_regex.is_match(page.get_url()) {
+        return false;
+      }
+    }
+
+    if let Some(ref content_types) = self.content_types {
+      let content_type = page
+        .headers
+        .as_ref()
+        .and_then(|headers| headers.get(CONTENT_TYPE))
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.trim().to_ascii_lowercase());
+
+      match content_type {
+        Some(content_type) => {
+          if !content_types
+            .iter()
+            .any(|t| content_type.starts_with(t.as_str()))
+          {
+            return false;
+          }
+        }
+        _ => return false,
+      }
+    }
+
+    true
+  }
+}
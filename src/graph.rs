@@ -0,0 +1,57 @@
+use crate::utils::url_key;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// the depth and referrer of a link.
+type Origin = (u32, Option<String>);
+
+/// tracks how each link was reached from the pages crawled. Requires the page links to be returned.
+#[derive(Debug, Default, Clone)]
+pub struct LinkGraph {
+  /// the depth and referrer of the links found keyed by url.
+  origins: Arc<Mutex<HashMap<String, Origin>>>,
+}
+
+impl LinkGraph {
+  /// forget the links recorded and seed the start url of the next run at depth 0.
+  pub fn reset(&self, start_url: &str) {
+    let mut origins = match self.origins.lock() {
+      Ok(origins) => origins,
+      Err(e) => e.into_inner(),
+    };
+
+    origins.clear();
+    origins.insert(url_key(start_url), (0, None));
+  }
+
+  /// record the links found on the page returning the depth and referrer of the page.
+  /// The links found are one hop deeper than the page with the page as the referrer. Recording a page again keeps the first origins.
+  pub fn record(&self, res: &spider::page::Page) -> (Option<u32>, Option<String>) {
+    let mut origins = match self.origins.lock() {
+      Ok(origins) => origins,
+      Err(e) => e.into_inner(),
+    };
+    let url = res.get_url();
+    let origin = origins.get(&url_key(url)).cloned();
+
+    if let (Some((depth, _)), Some(links)) = (&origin, &res.page_links) {
+      for link in links.iter() {
+        origins
+          .entry(url_key(link.as_ref()))
+          .or_insert_with(|| (depth + 1, Some(url.into())));
+      }
+    }
+
+    match origin {
+      Some((depth, referrer)) => (Some(depth), referrer),
+      _ => (None, None),
+    }
+  }
+
+  /// forget the links recorded.
+  pub fn clear(&self) {
+    if let Ok(mut origins) = self.origins.lock() {
+      origins.clear();
+    }
+  }
+}
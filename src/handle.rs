@@ -0,0 +1,122 @@
+use crate::callback::CallbackErrors;
+use crate::CrawlReport;
+use pyo3::prelude::*;
+use spider::tokio::sync::watch;
+use spider::tokio::task::AbortHandle;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// a handle to a crawl running in the background.
+#[derive(Clone)]
+#[pyclass]
+pub struct CrawlHandle {
+  #[pyo3(get)]
+  /// The crawl id used with website.stop.
+  pub id: u32,
+  /// abort the crawl task.
+  abort_handle: Arc<AbortHandle>,
+  /// is the crawl complete.
+  done: watch::Receiver<bool>,
+  /// the report of the crawl once complete.
+  report: Arc<Mutex<Option<CrawlReport>>>,
+  /// the callback exceptions of the website.
+  callback_errors: CallbackErrors,
+  /// the amount of callback exceptions before the crawl started.
+  errors_start: usize,
+}
+
+impl CrawlHandle {
+  /// a new crawl handle.
+  pub fn new(
+    id: u32,
+    abort_handle: AbortHandle,
+    done: watch::Receiver<bool>,
+    report: Arc<Mutex<Option<CrawlReport>>>,
+    callback_errors: CallbackErrors,
+  ) -> Self {
+    let errors_start = callback_errors.len();
+
+    CrawlHandle {
+      id,
+      abort_handle: Arc::new(abort_handle),
+      done,
+      report,
+      callback_errors,
+      errors_start,
+    }
+  }
+
+  /// the report of the crawl if complete.
+  fn get_report(&self) -> Option<CrawlReport> {
+    match self.report.lock() {
+      Ok(report) => report.clone(),
+      _ => None,
+    }
+  }
+}
+
+#[pymethods]
+impl CrawlHandle {
+  /// is the crawl still running.
+  pub fn is_running(&self) -> bool {
+    !self.abort_handle.is_finished()
+  }
+
+  /// cancel the crawl.
+  pub fn cancel(&self) {
+    self.abort_handle.abort();
+  }
+
+  /// get the report of the crawl. This is None until the crawl completes.
+  #[getter]
+  pub fn report(&self) -> Option<CrawlReport> {
+    self.get_report()
+  }
+
+  /// block until the crawl completes or the timeout in seconds elapses. Returns the report or None on timeout.
+  #[pyo3(signature = (timeout=None))]
+  pub fn join(&self, py: Python<'_>, timeout: Option<f64>) -> PyResult<Option<CrawlReport>> {
+    let mut done = self.done.clone();
+
+    // release the GIL while waiting on the crawl.
+    let completed = py.allow_threads(|| {
+      pyo3_async_runtimes::tokio::get_runtime().block_on(async move {
+        // a timeout too large for a duration waits without one.
+        match timeout.and_then(|timeout| Duration::try_from_secs_f64(timeout.max(0.0)).ok()) {
+          Some(timeout) => spider::tokio::time::timeout(timeout, done.wait_for(|done| *done))
+            .await
+            .is_ok(),
+          _ => {
+            let _ = done.wait_for(|done| *done).await;
+            true
+          }
+        }
+      })
+    });
+
+    if completed {
+      self.callback_errors.raised_since(py, self.errors_start)?;
+      Ok(self.get_report())
+    } else {
+      Ok(None)
+    }
+  }
+
+  /// an awaitable that resolves with the report when the crawl completes.
+  pub fn wait<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+    let handle = self.clone();
+
+    pyo3_async_runtimes::tokio::future_into_py(py, async move {
+      let mut done = handle.done.clone();
+      let _ = done.wait_for(|done| *done).await;
+
+      Python::with_gil(|py| handle.callback_errors.raised_since(py, handle.errors_start))?;
+
+      Ok(handle.get_report())
+    })
+  }
+
+  fn __repr__(&self) -> String {
+    format!("CrawlHandle(id={}, running={})", self.id, self.is_running())
+  }
+}
@@ -7,16 +7,47 @@ lazy_static! {
   pub static ref BUFFER: usize = (num_cpus::get() * 20).max(88);
 }
 
+pub mod ai;
+pub mod callback;
+pub mod cron;
+pub mod element;
+pub mod encoding;
+pub mod extraction;
+pub mod fetch_error;
+pub mod filter;
+pub mod graph;
+pub mod handle;
+pub mod links;
+pub mod metadata;
 pub mod npage;
 pub mod nwebsite;
 pub mod page;
+pub mod receiver;
+pub mod report;
+pub mod resources;
+pub mod screenshot;
 pub mod shortcut;
+pub mod stream;
+pub mod structured_data;
+pub mod timing;
+pub mod transform;
 pub mod utils;
 pub mod website;
 
-pub use npage::{new_page, page_title, NPage};
+pub use ai::{AIResult, AIUsage};
+pub use cron::Cron;
+pub use element::Element;
+pub use fetch_error::FetchError;
+pub use handle::CrawlHandle;
+pub use links::Link;
+pub use metadata::PageMetadata;
+pub use npage::{new_page, page_title, NPage, PageOptions};
 pub use nwebsite::NWebsite;
 pub use page::Page;
+pub use report::CrawlReport;
+pub use resources::PageResources;
+pub use screenshot::Screenshot;
+pub use stream::{PageIter, PageStream};
 pub use utils::pydict_to_json_value;
 pub use website::Website;
 
@@ -36,6 +67,20 @@ fn spider_rs(m: &Bound<'_, PyModule>) -> PyResult<()> {
   m.add_function(wrap_pyfunction!(crawl, m)?)?;
   m.add_class::<Website>()?;
   m.add_class::<Page>()?;
+  m.add_class::<NPage>()?;
+  m.add_class::<PageMetadata>()?;
+  m.add_class::<Element>()?;
+  m.add_class::<Link>()?;
+  m.add_class::<PageResources>()?;
+  m.add_class::<FetchError>()?;
+  m.add_class::<CrawlReport>()?;
+  m.add_class::<AIResult>()?;
+  m.add_class::<AIUsage>()?;
+  m.add_class::<Screenshot>()?;
+  m.add_class::<CrawlHandle>()?;
+  m.add_class::<Cron>()?;
+  m.add_class::<PageStream>()?;
+  m.add_class::<PageIter>()?;
 
   Ok(())
 }
@@ -0,0 +1,304 @@
+use crate::utils::url_key;
+use pyo3::prelude::*;
+use scraper::{ElementRef, Html, Selector};
+use serde::{Deserialize, Serialize};
+use spider::lazy_static::lazy_static;
+use spider::url::Url;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+
+lazy_static! {
+  static ref LINK_SELECTOR: Selector =
+    Selector::parse("a[href], area[href], link[href], iframe[src]").unwrap();
+  static ref BASE_SELECTOR: Selector = Selector::parse("base[href]").unwrap();
+}
+
+/// a link found on the page with the element it came from.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[pyclass]
+pub struct Link {
+  #[pyo3(get)]
+  /// The absolute url of the link.
+  pub url: String,
+  #[pyo3(get)]
+  /// The anchor text. Area links use the alt text and iframes the title.
+  pub text: String,
+  #[pyo3(get)]
+  /// The rel values ex: nofollow, sponsored, ugc.
+  pub rel: Vec<String>,
+  #[pyo3(get)]
+  /// The tag of the element ex: a, area, link, iframe.
+  pub element: String,
+  #[pyo3(get)]
+  /// The attribute holding the url ex: href, src.
+  pub attribute: String,
+  #[pyo3(get)]
+  /// The link is on the website following the subdomains and tld rules.
+  pub internal: bool,
+  /// The crawler enqueued the link in the run. Read with the enqueued getter while the run is going.
+  pub enqueued: Option<bool>,
+  /// The reason the crawler skipped the link. Read with the skip_reason getter while the run is going.
+  pub skip_reason: Option<String>,
+  /// The outcomes of the run the link was found in.
+  #[serde(skip)]
+  outcomes: Option<LinkOutcomes>,
+}
+
+impl Link {
+  /// copy the outcome of the link from the run into the fields.
+  pub fn resolve(&mut self) {
+    if let Some(outcomes) = self.outcomes.take() {
+      let (enqueued, skip_reason) = outcomes.outcome(&self.url);
+
+      self.enqueued = enqueued;
+      self.skip_reason = skip_reason.map(|reason| reason.to_string());
+    }
+  }
+}
+
+#[pymethods]
+impl Link {
+  /// the link has the nofollow rel value.
+  pub fn is_nofollow(&self) -> bool {
+    self.rel.iter().any(|rel| rel == "nofollow")
+  }
+
+  /// the crawler enqueued the link. This is True once the page of the link is crawled, False when the link was skipped,
+  /// and None while the run is going or when the link did not come from a crawl.
+  #[getter]
+  pub fn enqueued(&self) -> Option<bool> {
+    match self.outcomes {
+      Some(ref outcomes) => outcomes.outcome(&self.url).0,
+      _ => self.enqueued,
+    }
+  }
+
+  /// the reason the crawler skipped the link: robots, blacklist, budget, or not_crawled for the links outside the
+  /// website or past the crawl limits. Requires website.with_return_page_links to be set to true for robots, blacklist, and budget.
+  #[getter]
+  pub fn skip_reason(&self) -> Option<String> {
+    match self.outcomes {
+      Some(ref outcomes) => outcomes
+        .outcome(&self.url)
+        .1
+        .map(|reason| reason.to_string()),
+      _ => self.skip_reason.clone(),
+    }
+  }
+
+  fn __repr__(&self) -> String {
+    format!(
+      "Link(url={:?}, text={:?}, element={:?}, internal={}, enqueued={:?})",
+      self.url,
+      self.text,
+      self.element,
+      self.internal,
+      self.enqueued()
+    )
+  }
+}
+
+/// the links crawled and skipped during a run.
+#[derive(Debug, Default)]
+struct Outcomes {
+  /// the lowercase urls of the pages crawled.
+  crawled: HashSet<String>,
+  /// the reason of the links skipped keyed by the lowercase url.
+  skipped: HashMap<String, &'static str>,
+  /// the run completed.
+  finished: bool,
+}
+
+/// the outcomes of the links of a run shared with the links sent to python.
+#[derive(Debug, Default, Clone)]
+pub struct LinkOutcomes {
+  /// the outcomes of the run.
+  inner: Arc<Mutex<Outcomes>>,
+}
+
+/// the key of a link matching the case insensitive urls of the crawler.
+fn outcome_key(url: &str) -> String {
+  url_key(url).to_lowercase()
+}
+
+impl LinkOutcomes {
+  /// lock the outcomes.
+  fn lock(&self) -> std::sync::MutexGuard<'_, Outcomes> {
+    match self.inner.lock() {
+      Ok(outcomes) => outcomes,
+      Err(e) => e.into_inner(),
+    }
+  }
+
+  /// forget the outcomes of the last run.
+  pub fn reset(&self) {
+    *self.lock() = Outcomes::default();
+  }
+
+  /// record a page crawled.
+  pub fn crawled(&self, url: &str) {
+    self.lock().crawled.insert(outcome_key(url));
+  }
+
+  /// complete the run with the pages visited and the links skipped with the reason.
+  pub fn finish<'a>(
+    &self,
+    visited: impl Iterator<Item = &'a str>,
+    skipped: Vec<(String, &'static str)>,
+  ) {
+    let mut outcomes = self.lock();
+
+    outcomes.crawled.extend(visited.map(outcome_key));
+    outcomes.skipped.extend(
+      skipped
+        .into_iter()
+        .map(|(url, reason)| (outcome_key(&url), reason)),
+    );
+    outcomes.finished = true;
+  }
+
+  /// the link was enqueued and the reason it was skipped.
+  pub fn outcome(&self, url: &str) -> (Option<bool>, Option<&'static str>) {
+    let key = outcome_key(url);
+    let outcomes = self.lock();
+
+    if outcomes.crawled.contains(&key) {
+      (Some(true), None)
+    } else if let Some(reason) = outcomes.skipped.get(&key) {
+      (Some(false), Some(*reason))
+    } else if outcomes.finished {
+      (Some(false), Some("not_crawled"))
+    } else {
+      (None, None)
+    }
+  }
+}
+
+/// the rules deciding the links internal to the website.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct LinkScope {
+  /// include the subdomains of the website.
+  pub subdomains: bool,
+  /// include the website on other top level domains.
+  pub tld: bool,
+}
+
+/// the host without the www prefix.
+fn bare_host(url: &Url) -> Option<&str> {
+  url.host_str().map(|host| host.trim_start_matches("www."))
+}
+
+/// the host without the top level domain.
+fn without_tld(host: &str) -> &str {
+  host.rsplit_once('.').map_or(host, |(name, _)| name)
+}
+
+impl LinkScope {
+  /// the url is internal to the website of the base url.
+  pub fn is_internal(&self, base: &Url, url: &Url) -> bool {
+    let (base_host, host) = match (bare_host(base), bare_host(url)) {
+      (Some(base_host), Some(host)) => (base_host, host),
+      _ => return false,
+    };
+
+    if base_host.eq_ignore_ascii_case(host) {
+      return true;
+    }
+
+    let is_subdomain = |base_host: &str, host: &str| {
+      host.len() > base_host.len()
+        && host.ends_with(base_host)
+        && host[..host.len() - base_host.len()].ends_with('.')
+    };
+
+    if self.subdomains && is_subdomain(base_host, host) {
+      return true;
+    }
+
+    if self.tld {
+      let (base_name, name) = (without_tld(base_host), without_tld(host));
+
+      return base_name == name || (self.subdomains && is_subdomain(base_name, name));
+    }
+
+    false
+  }
+}
+
+/// the text describing the link element.
+fn link_text(element: ElementRef<'_>) -> String {
+  let value = element.value();
+
+  match value.name() {
+    "area" => value.attr("alt").unwrap_or_default().trim().to_string(),
+    "iframe" | "link" => value.attr("title").unwrap_or_default().trim().to_string(),
+    _ => element
+      .text()
+      .flat_map(|text| text.split_whitespace())
+      .collect::<Vec<&str>>()
+      .join(" "),
+  }
+}
+
+/// the url to resolve the relative links of the document using the base element.
+pub fn base_url(document: &Html, page_url: &Url) -> Url {
+  document
+    .select(&BASE_SELECTOR)
+    .next()
+    .and_then(|base| base.value().attr("href"))
+    .and_then(|href| page_url.join(href.trim()).ok())
+    .unwrap_or_else(|| page_url.clone())
+}
+
+/// extract the links of the document. The outcomes of the run tell if the links were enqueued.
+pub fn extract_links(
+  document: &Html,
+  url: &str,
+  scope: LinkScope,
+  outcomes: Option<&LinkOutcomes>,
+) -> Vec<Link> {
+  let page_url = match Url::parse(url) {
+    Ok(page_url) => page_url,
+    _ => return Default::default(),
+  };
+  let base_url = base_url(document, &page_url);
+
+  document
+    .select(&LINK_SELECTOR)
+    .filter_map(|element| {
+      let value = element.value();
+      let attribute = if value.name() == "iframe" {
+        "src"
+      } else {
+        "href"
+      };
+      let mut link_url = base_url.join(value.attr(attribute)?.trim()).ok()?;
+
+      if !matches!(link_url.scheme(), "http" | "https") {
+        return None;
+      }
+
+      link_url.set_fragment(None);
+
+      let internal = scope.is_internal(&page_url, &link_url);
+      let url = link_url.to_string();
+
+      Some(Link {
+        enqueued: None,
+        skip_reason: None,
+        outcomes: outcomes.cloned(),
+        text: link_text(element),
+        rel: value
+          .attr("rel")
+          .unwrap_or_default()
+          .split_whitespace()
+          .map(|rel| rel.to_ascii_lowercase())
+          .collect(),
+        element: value.name().to_string(),
+        attribute: attribute.to_string(),
+        internal,
+        url,
+      })
+    })
+    .collect()
+}
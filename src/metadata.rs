@@ -0,0 +1,159 @@
+use crate::utils::json_value_to_pyobj;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use scraper::{Html, Selector};
+use serde::{Deserialize, Serialize};
+use spider::lazy_static::lazy_static;
+use std::collections::HashMap;
+
+lazy_static! {
+  static ref TITLE_SELECTOR: Selector = Selector::parse("title").unwrap();
+  static ref META_SELECTOR: Selector = Selector::parse("meta[content]").unwrap();
+  static ref LINK_SELECTOR: Selector = Selector::parse("link[rel][href]").unwrap();
+}
+
+/// the metadata found in the head of the page.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+#[pyclass]
+pub struct PageMetadata {
+  #[pyo3(get)]
+  /// The page title.
+  pub title: String,
+  #[pyo3(get)]
+  /// The meta description.
+  pub description: Option<String>,
+  #[pyo3(get)]
+  /// The meta keywords.
+  pub keywords: Vec<String>,
+  #[pyo3(get)]
+  /// The absolute canonical url.
+  pub canonical_url: Option<String>,
+  #[pyo3(get)]
+  /// The language set on the html element.
+  pub lang: Option<String>,
+  #[pyo3(get)]
+  /// The robots meta directives ex: noindex, nofollow.
+  pub robots: Vec<String>,
+  #[pyo3(get)]
+  /// The OpenGraph properties keyed by the property name ex: og:title.
+  pub open_graph: HashMap<String, String>,
+  #[pyo3(get)]
+  /// The Twitter card properties keyed by the name ex: twitter:card.
+  pub twitter: HashMap<String, String>,
+}
+
+#[pymethods]
+impl PageMetadata {
+  fn __repr__(&self) -> String {
+    format!(
+      "PageMetadata(title={:?}, description={:?}, canonical_url={:?}, lang={:?})",
+      self.title, self.description, self.canonical_url, self.lang
+    )
+  }
+
+  /// get the metadata as a dict.
+  pub fn to_dict(&self, py: Python<'_>) -> PyResult<PyObject> {
+    let value = serde_json::to_value(self).map_err(|e| PyValueError::new_err(e.to_string()))?;
+
+    json_value_to_pyobj(py, &value)
+  }
+}
+
+/// split a comma separated meta value.
+fn split_list(value: &str) -> Vec<String> {
+  value
+    .split(',')
+    .map(|v| v.trim())
+    .filter(|v| !v.is_empty())
+    .map(|v| v.to_string())
+    .collect()
+}
+
+/// is the content of the page html.
+pub fn is_html(res: &spider::page::Page) -> bool {
+  match res
+    .headers
+    .as_ref()
+    .and_then(|headers| headers.get(spider::reqwest::header::CONTENT_TYPE))
+    .and_then(|value| value.to_str().ok())
+  {
+    Some(content_type) => content_type.to_ascii_lowercase().contains("html"),
+    _ => res
+      .get_html_bytes_u8()
+      .iter()
+      .find(|b| !b.is_ascii_whitespace())
+      .is_some_and(|b| *b == b'<'),
+  }
+}
+
+/// extract the metadata of the document.
+pub fn extract_metadata(document: &Html, url: &str) -> PageMetadata {
+  let mut metadata = PageMetadata {
+    lang: document
+      .root_element()
+      .value()
+      .attr("lang")
+      .map(|lang| lang.trim().to_string())
+      .filter(|lang| !lang.is_empty()),
+    ..Default::default()
+  };
+
+  if let Some(title) = document.select(&TITLE_SELECTOR).next() {
+    metadata.title = title.text().collect::<String>().trim().to_string();
+  }
+
+  for element in document.select(&META_SELECTOR) {
+    let element = element.value();
+    let name = match element.attr("name").or_else(|| element.attr("property")) {
+      Some(name) => name.trim().to_ascii_lowercase(),
+      _ => continue,
+    };
+    let content = element.attr("content").unwrap_or_default().trim();
+
+    match name.as_str() {
+      "description" => {
+        if metadata.description.is_none() {
+          metadata.description = Some(content.to_string());
+        }
+      }
+      "keywords" => metadata.keywords.extend(split_list(content)),
+      "robots" => metadata
+        .robots
+        .extend(split_list(&content.to_ascii_lowercase())),
+      n if n.starts_with("og:") => {
+        metadata
+          .open_graph
+          .entry(name)
+          .or_insert_with(|| content.to_string());
+      }
+      n if n.starts_with("twitter:") => {
+        metadata
+          .twitter
+          .entry(name)
+          .or_insert_with(|| content.to_string());
+      }
+      _ => (),
+    }
+  }
+
+  for element in document.select(&LINK_SELECTOR) {
+    let element = element.value();
+    let canonical = element
+      .attr("rel")
+      .unwrap_or_default()
+      .split_ascii_whitespace()
+      .any(|rel| rel.eq_ignore_ascii_case("canonical"));
+
+    if canonical {
+      let href = element.attr("href").unwrap_or_default().trim();
+      metadata.canonical_url = match spider::url::Url::parse(url).and_then(|base| base.join(href)) {
+        Ok(canonical_url) => Some(canonical_url.to_string()),
+        _ => Some(href.to_string()),
+      };
+      break;
+    }
+  }
+
+  metadata
+}
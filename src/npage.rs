@@ -1,34 +1,186 @@
+use crate::ai::{ai_results, AIResult, AIUsage};
+use crate::element::Element;
+use crate::encoding::{decode, detect_page};
+use crate::extraction::ExtractionSchema;
+use crate::fetch_error::{fetch_error, FetchError};
+use crate::graph::LinkGraph;
+use crate::links::{extract_links, Link, LinkOutcomes, LinkScope};
+use crate::metadata::{extract_metadata, is_html, PageMetadata};
 use crate::page::header_map_to_hash_map;
+use crate::resources::{extract_resources, PageResources};
+use crate::screenshot::Screenshot;
+use crate::structured_data::extract_structured_data;
+use crate::timing::FetchTimings;
+use crate::transform::{to_markdown, to_text, ReturnFormat};
+use crate::utils::{json_value_to_pyobj, pyobj_to_bytes, pyobj_to_json_value};
+use bytes::Bytes;
+use pyo3::exceptions::PyValueError;
 use pyo3::prelude::*;
+use pyo3::sync::GILOnceCell;
+use pyo3::types::{PyBytes, PyDict, PyType};
+use scraper::{Html, Selector};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
 use spider::lazy_static::lazy_static;
 use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
 
 lazy_static! {
-  static ref TITLE_SELECTOR: scraper::Selector = scraper::Selector::parse("title").unwrap();
+  static ref TITLE_SELECTOR: Selector = Selector::parse("title").unwrap();
 }
 
 /// a simple page object
-#[derive(Default, Clone)]
-#[pyclass]
+#[derive(Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+#[pyclass(module = "spider_rs")]
 pub struct NPage {
   #[pyo3(get)]
   /// The url of the resource.
   pub url: String,
   #[pyo3(get)]
-  /// The content of the page found as UTF-8.
+  /// The content of the page found as UTF-8 in the website return format.
   pub content: String,
   #[pyo3(get)]
   /// The HTTP status code.
   pub status_code: u16,
   #[pyo3(get)]
-  /// The raw content in bytes.
-  pub raw_content: Option<Vec<u8>>,
+  /// The encoding used to decode the content ex: UTF-8, Shift_JIS, windows-1252.
+  pub encoding: String,
+  /// The raw content in bytes shared with the crawled page.
+  #[serde(with = "crate::utils::base64_bytes_option")]
+  pub raw_content: Option<Bytes>,
+  /// The python bytes of the raw content created on the first read.
+  #[serde(skip)]
+  raw_bytes: Arc<GILOnceCell<Py<PyBytes>>>,
   #[pyo3(get)]
   /// The HTTP headers.
   pub headers: Option<HashMap<String, String>>,
   #[pyo3(get)]
   /// The links found on the page. Requires the website.builder method website.with_subscription_return_page_links to be set to true.
   pub links: Option<HashSet<String>>,
+  #[pyo3(get)]
+  /// The links found on the page with the anchor text, rel values, and source element. Requires the website.builder method website.with_rich_links to be set to true.
+  pub rich_links: Option<Vec<Link>>,
+  #[pyo3(get)]
+  /// The images, scripts, stylesheets, fonts, media, and iframes referenced by the page. Requires the website.builder method website.with_page_resources to be set to true.
+  pub resources: Option<PageResources>,
+  #[pyo3(get)]
+  /// The hops from the start url. Requires the website.builder method website.with_return_page_links to be set to true for pages past the start url.
+  pub depth: Option<u32>,
+  #[pyo3(get)]
+  /// The url of the page whose link led here. This is None for the start url.
+  pub referrer: Option<String>,
+  #[pyo3(get)]
+  /// The final url of the resource after the redirects.
+  pub final_url: String,
+  #[pyo3(get)]
+  /// The requested url when the page was redirected to the final url. This is None when the page was not redirected.
+  pub redirected_from: Option<String>,
+  #[pyo3(get)]
+  /// The seconds from the start of the request until the crawl sent the page.
+  pub duration: Option<f64>,
+  #[pyo3(get)]
+  /// The seconds from the start of the request until the response headers were received. This is None for pages rendered with chrome.
+  pub ttfb: Option<f64>,
+  #[pyo3(get)]
+  /// The HTTP version of the response ex: HTTP/1.1, HTTP/2.0. This is None for pages rendered with chrome.
+  pub http_version: Option<String>,
+  #[pyo3(get)]
+  /// The size of the response in bytes.
+  pub response_size: u64,
+  #[pyo3(get)]
+  /// The error of the request when the page failed to fetch.
+  pub error: Option<FetchError>,
+  #[pyo3(get)]
+  /// The screenshot of the page. Requires the website.builder method website.with_screenshot to be set with bytes enabled.
+  pub screenshot: Option<Screenshot>,
+  #[pyo3(get)]
+  /// The results of the OpenAI prompts. Requires the website.builder method website.with_openai to be set.
+  pub ai_results: Option<Vec<AIResult>>,
+  #[pyo3(get)]
+  /// The OpenAI tokens used for the page.
+  pub ai_usage: Option<AIUsage>,
+  #[pyo3(get)]
+  /// The metadata found in the head of the page. This is None for non html resources.
+  pub metadata: Option<PageMetadata>,
+  /// The fields extracted with the website extraction schema.
+  pub extracted: Option<JsonValue>,
+  /// The JSON-LD blocks, microdata items, and RDFa items found on the page.
+  pub structured_data: Option<JsonValue>,
+  /// The format of the content.
+  pub return_format: ReturnFormat,
+  /// The parsed document cached after the first selector query.
+  #[serde(skip)]
+  document: Arc<Mutex<Option<Html>>>,
+}
+
+/// the options used to build the pages sent to python.
+#[derive(Debug, Default, Clone)]
+pub struct PageOptions {
+  /// keep the bytes of the page set from the website constructor. The raw return format also keeps the bytes.
+  pub raw_content: bool,
+  /// the schema of fields extracted from each page.
+  pub extraction: Option<Arc<ExtractionSchema>>,
+  /// the format of the page content.
+  pub return_format: ReturnFormat,
+  /// the depth and referrer of the links crawled.
+  pub graph: LinkGraph,
+  /// extract the links found with the element details.
+  pub rich_links: bool,
+  /// the rules deciding the links internal to the website.
+  pub link_scope: LinkScope,
+  /// list the resources referenced by each page.
+  pub resources: bool,
+  /// extract the JSON-LD, microdata, and RDFa of each page.
+  pub structured_data: bool,
+  /// the timings of the pages fetched.
+  pub timings: FetchTimings,
+  /// the links crawled and skipped during the run.
+  pub outcomes: LinkOutcomes,
+}
+
+impl PageOptions {
+  /// the options of a website. Setting raw_content keeps the bytes without converting the content to UTF-8.
+  pub fn new(raw_content: bool) -> Self {
+    PageOptions {
+      raw_content,
+      return_format: if raw_content {
+        ReturnFormat::Raw
+      } else {
+        ReturnFormat::Html
+      },
+      ..Default::default()
+    }
+  }
+
+  /// keep the bytes of the page.
+  fn keeps_bytes(&self) -> bool {
+    self.raw_content || self.return_format == ReturnFormat::Raw
+  }
+
+  /// an option reads the body of the page.
+  fn reads_body(&self) -> bool {
+    self.extraction.is_some()
+      || self.rich_links
+      || self.resources
+      || self.structured_data
+      || matches!(
+        self.return_format,
+        ReturnFormat::Markdown | ReturnFormat::Text
+      )
+  }
+}
+
+/// the html up to the end of the head.
+fn html_head(html: &str) -> &str {
+  match html
+    .as_bytes()
+    .windows(7)
+    .position(|w| w.eq_ignore_ascii_case(b"</head>"))
+  {
+    Some(end) => &html[..end + 7],
+    _ => html,
+  }
 }
 
 /// get the page title.
@@ -37,17 +189,89 @@ pub fn page_title(page: NPage) -> String {
 }
 
 /// get a new Page
-pub fn new_page(res: &spider::page::Page, raw: bool) -> NPage {
+pub fn new_page(res: &spider::page::Page, options: &PageOptions) -> NPage {
+  let raw = options.return_format == ReturnFormat::Raw;
+  let html_page = is_html(res);
+  let encoding = detect_page(res);
+  let html = if raw && !html_page {
+    Default::default()
+  } else {
+    decode(res.get_html_bytes_u8(), encoding)
+  };
+  // the metadata only needs the head, the full document is parsed when an option reads the body.
+  let document = match html_page {
+    true if options.reads_body() => Some(Html::parse_document(&html)),
+    true => Some(Html::parse_document(html_head(&html))),
+    _ => None,
+  };
+  let metadata = document
+    .as_ref()
+    .map(|document| extract_metadata(document, res.get_url()));
+  let extracted = match (&options.extraction, &document) {
+    (Some(schema), Some(document)) => Some(schema.extract(document)),
+    _ => None,
+  };
+
+  let rich_links = match document {
+    Some(ref document) if options.rich_links => Some(extract_links(
+      document,
+      res.get_url(),
+      options.link_scope,
+      Some(&options.outcomes),
+    )),
+    _ => None,
+  };
+  let resources = match document {
+    Some(ref document) if options.resources => Some(extract_resources(document, res.get_url())),
+    _ => None,
+  };
+  let structured_data = match document {
+    Some(ref document) if options.structured_data => {
+      Some(extract_structured_data(document, res.get_url()))
+    }
+    _ => None,
+  };
+  let duration = options.timings.complete(res);
+  let (ttfb, http_version) = options.timings.request(res.get_url());
+  let (depth, referrer) = options.graph.record(res);
+  let final_url = res.get_url_final();
+  let return_format = match (options.return_format, &document) {
+    _ if raw => ReturnFormat::Raw,
+    (ReturnFormat::Markdown, Some(_)) => ReturnFormat::Markdown,
+    (ReturnFormat::Text, Some(_)) => ReturnFormat::Text,
+    _ => ReturnFormat::Html,
+  };
+  let redirected_from = if final_url != res.get_url() {
+    Some(res.get_url().into())
+  } else {
+    None
+  };
+
   NPage {
     url: res.get_url().into(),
+    rich_links,
+    resources,
+    depth,
+    referrer,
+    final_url: final_url.into(),
+    redirected_from,
+    duration: Some(duration),
+    ttfb,
+    http_version,
+    response_size: match res.bytes_transferred {
+      Some(bytes) => bytes as u64,
+      _ => res.get_html_bytes_u8().len() as u64,
+    },
     status_code: res.status_code.as_u16(),
-    content: if raw {
-      Default::default()
-    } else {
-      res.get_html()
+    content: match (return_format, &document) {
+      (ReturnFormat::Raw, _) => Default::default(),
+      (ReturnFormat::Markdown, Some(document)) => to_markdown(document, res.get_url()),
+      (ReturnFormat::Text, Some(document)) => to_text(document),
+      _ => html,
     },
-    raw_content: if raw {
-      Some(res.get_html_bytes_u8().into())
+    encoding: encoding.into(),
+    raw_content: if options.keeps_bytes() {
+      Some(res.get_bytes().cloned().unwrap_or_default())
     } else {
       None
     },
@@ -64,20 +288,293 @@ pub fn new_page(res: &spider::page::Page, raw: bool) -> NPage {
       ),
       _ => None,
     },
+    error: fetch_error(res),
+    screenshot: res.screenshot_bytes.clone().map(Screenshot::new),
+    ai_results: ai_results(res),
+    ai_usage: AIUsage::from_page(res),
+    metadata,
+    extracted,
+    structured_data,
+    return_format,
+    document: Default::default(),
+  }
+}
+
+impl NPage {
+  /// use the parsed document of the page. The document is parsed on first use and kept for the next queries.
+  pub fn with_document<R>(&self, f: impl FnOnce(&Html) -> R) -> PyResult<R> {
+    let mut document = match self.document.lock() {
+      Ok(document) => document,
+      Err(e) => e.into_inner(),
+    };
+
+    let parsed = match document.take() {
+      Some(parsed) => parsed,
+      _ => self.parse_document()?,
+    };
+
+    Ok(f(document.insert(parsed)))
+  }
+
+  /// a copy of the page with the outcome of the rich links read from the run.
+  fn resolved(&self) -> NPage {
+    let mut page = self.clone();
+
+    for link in page.rich_links.iter_mut().flatten() {
+      link.resolve();
+    }
+
+    page
   }
+
+  /// parse the html of the page from the raw bytes or the html content.
+  fn parse_document(&self) -> PyResult<Html> {
+    match self.raw_content {
+      Some(ref raw_content) => Ok(Html::parse_document(&decode(raw_content, &self.encoding))),
+      _ if self.return_format == ReturnFormat::Html => Ok(Html::parse_document(&self.content)),
+      _ => Err(PyValueError::new_err(
+        "The page content is not html, use the html or raw return format to query the document",
+      )),
+    }
+  }
+}
+
+/// parse a css selector for python.
+fn parse_selector(css: &str) -> PyResult<Selector> {
+  Selector::parse(css).map_err(|e| {
+    pyo3::exceptions::PyValueError::new_err(format!("Invalid css selector {css}: {e}"))
+  })
 }
 
 #[pymethods]
 impl NPage {
+  /// a new page from the dict of to_dict.
+  #[new]
+  #[pyo3(signature = (data=None))]
+  fn py_new(data: Option<&Bound<'_, PyDict>>) -> PyResult<Self> {
+    match data {
+      Some(data) => NPage::from_dict(data),
+      _ => Ok(NPage::default()),
+    }
+  }
+
   fn __call__(&self) {}
 
-  /// the html page title. TODO: remove for built in spider title passing.
+  /// the page failed to fetch.
+  pub fn is_error(&self) -> bool {
+    self.error.is_some()
+  }
+
+  /// the html page title.
   pub fn title(&self) -> String {
-    use scraper::Html;
-    let fragment: Html = Html::parse_document(&self.content);
-    match fragment.select(&TITLE_SELECTOR).next() {
-      Some(title) => title.inner_html(),
-      _ => Default::default(),
+    if let Some(ref metadata) = self.metadata {
+      return metadata.title.clone();
+    }
+
+    self
+      .with_document(|document| match document.select(&TITLE_SELECTOR).next() {
+        Some(title) => title.inner_html(),
+        _ => Default::default(),
+      })
+      .unwrap_or_default()
+  }
+
+  /// get the raw content as bytes.
+  #[getter]
+  pub fn raw_content<'py>(&self, py: Python<'py>) -> Option<Bound<'py, PyBytes>> {
+    self.raw_content.as_ref().map(|raw_content| {
+      self
+        .raw_bytes
+        .get_or_init(py, || PyBytes::new(py, raw_content).unbind())
+        .bind(py)
+        .clone()
+    })
+  }
+
+  /// get the page as a dict. The raw content and the screenshot data are bytes.
+  pub fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+    let page = NPage {
+      raw_content: None,
+      screenshot: None,
+      ..self.resolved()
+    };
+    let value = serde_json::to_value(&page).map_err(|e| PyValueError::new_err(e.to_string()))?;
+    let dict = json_value_to_pyobj(py, &value)?
+      .into_bound(py)
+      .downcast_into::<PyDict>()?;
+
+    if let Some(raw_content) = self.raw_content(py) {
+      dict.set_item("raw_content", raw_content)?;
+    }
+
+    if let Some(ref screenshot) = self.screenshot {
+      let screenshot_dict = PyDict::new(py);
+      screenshot_dict.set_item("format", &screenshot.format)?;
+      screenshot_dict.set_item("data", PyBytes::new(py, &screenshot.data))?;
+      dict.set_item("screenshot", screenshot_dict)?;
+    }
+
+    Ok(dict)
+  }
+
+  /// get the page as JSON. The raw content and the screenshot data are base64 encoded.
+  pub fn to_json(&self) -> PyResult<String> {
+    serde_json::to_string(&self.resolved()).map_err(|e| PyValueError::new_err(e.to_string()))
+  }
+
+  /// build a page from the dict of to_dict.
+  #[staticmethod]
+  pub fn from_dict(data: &Bound<'_, PyDict>) -> PyResult<NPage> {
+    let mut fields = serde_json::Map::new();
+    let mut raw_content = None;
+    let mut screenshot = None;
+
+    for (key, value) in data.iter() {
+      let key: String = key.extract()?;
+
+      match key.as_str() {
+        _ if value.is_none() => (),
+        "raw_content" => raw_content = Some(pyobj_to_bytes(&value)?.into()),
+        "screenshot" => {
+          screenshot = Some(Screenshot {
+            format: value.get_item("format")?.extract()?,
+            data: pyobj_to_bytes(&value.get_item("data")?)?,
+          })
+        }
+        _ => {
+          fields.insert(key, pyobj_to_json_value(&value)?);
+        }
+      }
+    }
+
+    let mut page: NPage = serde_json::from_value(JsonValue::Object(fields))
+      .map_err(|e| PyValueError::new_err(e.to_string()))?;
+
+    page.raw_content = raw_content;
+    page.screenshot = screenshot;
+
+    Ok(page)
+  }
+
+  /// build a page from the JSON of to_json.
+  #[staticmethod]
+  pub fn from_json(json: &str) -> PyResult<NPage> {
+    serde_json::from_str(json).map_err(|e| PyValueError::new_err(e.to_string()))
+  }
+
+  fn __getstate__<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+    self.to_dict(py)
+  }
+
+  fn __setstate__(&mut self, state: &Bound<'_, PyDict>) -> PyResult<()> {
+    *self = NPage::from_dict(state)?;
+    Ok(())
+  }
+
+  fn __reduce__<'py>(
+    &self,
+    py: Python<'py>,
+  ) -> PyResult<(Bound<'py, PyType>, (Bound<'py, PyDict>,))> {
+    Ok((py.get_type::<NPage>(), (self.to_dict(py)?,)))
+  }
+
+  /// get the fields extracted with the website extraction schema as a dict.
+  #[getter]
+  pub fn extracted(&self, py: Python<'_>) -> PyResult<Option<PyObject>> {
+    match self.extracted {
+      Some(ref extracted) => Ok(Some(json_value_to_pyobj(py, extracted)?)),
+      _ => Ok(None),
+    }
+  }
+
+  /// convert the html of the page to markdown keeping the headings, lists, links, and tables.
+  pub fn to_markdown(&self) -> PyResult<String> {
+    match self.return_format {
+      ReturnFormat::Markdown => Ok(self.content.clone()),
+      _ => self.with_document(|document| to_markdown(document, &self.url)),
+    }
+  }
+
+  /// convert the html of the page to plain text.
+  pub fn to_text(&self) -> PyResult<String> {
+    match self.return_format {
+      ReturnFormat::Text => Ok(self.content.clone()),
+      _ => self.with_document(to_text),
+    }
+  }
+
+  /// get the JSON-LD blocks, microdata items, and RDFa items as a dict with the json_ld, microdata, and rdfa lists.
+  #[getter]
+  pub fn structured_data(&self, py: Python<'_>) -> PyResult<Option<PyObject>> {
+    match self.structured_data {
+      Some(ref structured_data) => Ok(Some(json_value_to_pyobj(py, structured_data)?)),
+      _ => Ok(None),
+    }
+  }
+
+  /// get all of the elements matching the css selector.
+  pub fn select(&self, css: &str) -> PyResult<Vec<Element>> {
+    let selector = parse_selector(css)?;
+
+    self.with_document(|document| {
+      document
+        .select(&selector)
+        .map(|element| Element::new(element, &self.document))
+        .collect()
+    })
+  }
+
+  /// get the first element matching the css selector.
+  pub fn select_one(&self, css: &str) -> PyResult<Option<Element>> {
+    let selector = parse_selector(css)?;
+
+    self.with_document(|document| {
+      document
+        .select(&selector)
+        .next()
+        .map(|element| Element::new(element, &self.document))
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn page() -> NPage {
+    NPage {
+      url: "https://example.com".into(),
+      status_code: 200,
+      raw_content: Some(Bytes::from_static(b"<html>\xff\x00\x80</html>")),
+      screenshot: Some(Screenshot::new(b"\x89PNG\r\n\x1a\n\x00\xff".to_vec())),
+      links: Some(HashSet::from(["https://example.com/a".to_string()])),
+      return_format: ReturnFormat::Raw,
+      ..Default::default()
     }
   }
+
+  #[test]
+  fn json_round_trip_keeps_the_bytes() {
+    let page = page();
+    let restored = NPage::from_json(&page.to_json().unwrap()).unwrap();
+
+    assert_eq!(restored.url, page.url);
+    assert_eq!(restored.links, page.links);
+    assert_eq!(restored.return_format, ReturnFormat::Raw);
+    assert_eq!(restored.raw_content, page.raw_content);
+
+    let screenshot = restored.screenshot.unwrap();
+    assert_eq!(screenshot.format, "png");
+    assert_eq!(Some(screenshot.data), page.screenshot.map(|s| s.data));
+  }
+
+  #[test]
+  fn value_round_trip_keeps_the_fields() {
+    let page = page();
+    let restored: NPage = serde_json::from_value(serde_json::to_value(&page).unwrap()).unwrap();
+
+    assert_eq!(restored.status_code, 200);
+    assert_eq!(restored.raw_content, page.raw_content);
+    assert_eq!(restored.links, page.links);
+  }
 }
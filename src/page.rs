@@ -1,3 +1,4 @@
+use crate::links::{extract_links, Link, LinkScope};
 use pyo3::{pyclass, pymethods, PyRef, PyRefMut};
 use spider::{compact_str::CompactString, hashbrown::HashSet, reqwest::header::HeaderMap};
 use std::collections::HashMap;
@@ -78,21 +79,24 @@ impl Page {
         client
       };
     }
-    let s = pyo3_async_runtimes::tokio::get_runtime()
-      .block_on(async move {
-        let page = spider::page::Page::new_page(&slf.url, &PAGE_CLIENT).await;
-        slf.status_code = page.status_code.into();
-        slf.inner = Some(page);
-        slf.selectors = Some(spider::page::get_page_selectors(
-          &slf.url,
-          slf.subdomains.unwrap_or_default(),
-          slf.tld.unwrap_or_default(),
-        ));
-        Ok::<PyRefMut<'_, Page>, ()>(slf)
-      })
-      .unwrap();
-
-    s
+    let py = slf.py();
+    let url = slf.url.clone();
+
+    // release the GIL while the request is in flight.
+    let page = py.allow_threads(|| {
+      pyo3_async_runtimes::tokio::get_runtime()
+        .block_on(async move { spider::page::Page::new_page(&url, &PAGE_CLIENT).await })
+    });
+
+    slf.status_code = page.status_code.into();
+    slf.inner = Some(page);
+    slf.selectors = Some(spider::page::get_page_selectors(
+      &slf.url,
+      slf.subdomains.unwrap_or_default(),
+      slf.tld.unwrap_or_default(),
+    ));
+
+    slf
   }
 
   /// all links on the page
@@ -100,12 +104,14 @@ impl Page {
     match &slf.selectors {
       Some(selectors) => match &slf.inner {
         Some(inner) => {
-          let links = pyo3_async_runtimes::tokio::get_runtime()
-            .block_on(async move {
-              let links = inner.to_owned().links(&selectors, &None).await;
-              Ok::<spider::hashbrown::HashSet<spider::CaseInsensitiveString>, ()>(links)
-            })
-            .unwrap_or_default();
+          let links = slf.py().allow_threads(|| {
+            pyo3_async_runtimes::tokio::get_runtime()
+              .block_on(async move {
+                let links = inner.to_owned().links(&selectors, &None).await;
+                Ok::<spider::hashbrown::HashSet<spider::CaseInsensitiveString>, ()>(links)
+              })
+              .unwrap_or_default()
+          });
 
           links
             .into_iter()
@@ -118,6 +124,22 @@ impl Page {
     }
   }
 
+  /// all links on the page with the anchor text, rel values, source element, and internal or external.
+  pub fn get_rich_links(&self) -> Vec<Link> {
+    match &self.inner {
+      Some(inner) => {
+        let document = scraper::Html::parse_document(&inner.get_html());
+        let scope = LinkScope {
+          subdomains: self.subdomains.unwrap_or_default(),
+          tld: self.tld.unwrap_or_default(),
+        };
+
+        extract_links(&document, &self.url, scope, None)
+      }
+      _ => Default::default(),
+    }
+  }
+
   /// get the html for the page
   pub fn get_html(&self) -> String {
     match &self.inner {
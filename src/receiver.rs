@@ -0,0 +1,143 @@
+use spider::page::Page;
+use spider::tokio::sync::broadcast::{
+  self,
+  error::{RecvError, TryRecvError},
+};
+use spider::tokio::sync::mpsc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+/// how a subscriber handles falling behind the crawl.
+#[derive(Debug, Default, Clone)]
+pub struct LagPolicy {
+  /// keep receiving pages after lagging instead of ending the subscription.
+  pub keep_on_lag: bool,
+  /// the pages dropped by the subscriber.
+  dropped: Arc<AtomicU64>,
+  /// the pages dropped across all of the website subscribers.
+  total: Arc<AtomicU64>,
+}
+
+impl LagPolicy {
+  /// a policy for a new subscriber sharing the website total.
+  pub fn subscriber(&self, keep_on_lag: Option<bool>) -> Self {
+    LagPolicy {
+      keep_on_lag: keep_on_lag.unwrap_or(self.keep_on_lag),
+      dropped: Default::default(),
+      total: self.total.clone(),
+    }
+  }
+
+  /// record the pages skipped from lagging.
+  fn record(&self, skipped: u64) {
+    self.dropped.fetch_add(skipped, Ordering::Relaxed);
+    self.total.fetch_add(skipped, Ordering::Relaxed);
+  }
+
+  /// the pages dropped by the subscriber.
+  pub fn dropped_pages(&self) -> u64 {
+    self.dropped.load(Ordering::Relaxed)
+  }
+
+  /// the pages dropped across all of the website subscribers.
+  pub fn total_dropped_pages(&self) -> u64 {
+    self.total.load(Ordering::Relaxed)
+  }
+}
+
+/// where the pages are read from.
+enum Source {
+  /// directly from the website channel.
+  Channel(broadcast::Receiver<Page>, LagPolicy),
+  /// from a dedicated buffer filled from the website channel.
+  Buffered(mpsc::Receiver<Page>),
+}
+
+/// receives the pages from the website channel applying the lag policy.
+pub struct PageReceiver {
+  /// the source of the pages.
+  source: Source,
+}
+
+impl PageReceiver {
+  /// a new receiver. Setting the buffer size gives the subscriber a dedicated queue on top of the website channel.
+  pub fn new(rx: broadcast::Receiver<Page>, lag: LagPolicy, buffer_size: Option<usize>) -> Self {
+    let source = match buffer_size {
+      Some(buffer_size) => {
+        let (tx, buffered) = mpsc::channel(buffer_size.max(1));
+        let mut rx = rx;
+
+        pyo3_async_runtimes::tokio::get_runtime().spawn(async move {
+          while let Some(page) = recv_channel(&mut rx, &lag).await {
+            if tx.send(page).await.is_err() {
+              break;
+            }
+          }
+        });
+
+        Source::Buffered(buffered)
+      }
+      _ => Source::Channel(rx, lag),
+    };
+
+    PageReceiver { source }
+  }
+
+  /// receive the next page. Returns None when the subscription ends.
+  pub async fn recv(&mut self) -> Option<Page> {
+    match &mut self.source {
+      Source::Channel(rx, lag) => recv_channel(rx, lag).await,
+      Source::Buffered(rx) => rx.recv().await,
+    }
+  }
+
+  /// receive a page already sent without waiting.
+  pub fn try_recv(&mut self) -> Option<Page> {
+    match &mut self.source {
+      Source::Channel(rx, lag) => loop {
+        match rx.try_recv() {
+          Ok(page) => return Some(page),
+          Err(TryRecvError::Lagged(skipped)) => {
+            lag.record(skipped);
+
+            if !lag.keep_on_lag {
+              return None;
+            }
+          }
+          _ => return None,
+        }
+      },
+      Source::Buffered(rx) => rx.try_recv().ok(),
+    }
+  }
+}
+
+/// subscribe to the website channel.
+pub fn subscribe(
+  website: &mut spider::website::Website,
+  lag: LagPolicy,
+  buffer_size: Option<usize>,
+) -> PageReceiver {
+  let rx = website
+    .subscribe(*crate::BUFFER / 2)
+    .expect("sync feature should be enabled");
+
+  PageReceiver::new(rx, lag, buffer_size)
+}
+
+/// receive the next page from the website channel counting the pages skipped from lagging.
+async fn recv_channel(rx: &mut broadcast::Receiver<Page>, lag: &LagPolicy) -> Option<Page> {
+  loop {
+    match rx.recv().await {
+      Ok(page) => return Some(page),
+      Err(RecvError::Lagged(skipped)) => {
+        lag.record(skipped);
+
+        if !lag.keep_on_lag {
+          return None;
+        }
+      }
+      Err(RecvError::Closed) => return None,
+    }
+  }
+}
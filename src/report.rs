@@ -0,0 +1,284 @@
+use crate::ai::AIUsage;
+use crate::links::LinkOutcomes;
+use crate::npage::PageOptions;
+use crate::BUFFER;
+use pyo3::prelude::*;
+use spider::hashbrown::HashSet;
+use spider::tokio::select;
+use spider::tokio::sync::broadcast::error::{RecvError, TryRecvError};
+use spider::tokio::sync::oneshot;
+use spider::tokio::task::JoinHandle;
+use spider::website::ProcessLinkStatus;
+use spider::CaseInsensitiveString;
+use std::collections::HashMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// the summary of a crawl run.
+#[derive(Debug, Default, Clone)]
+#[pyclass]
+pub struct CrawlReport {
+  #[pyo3(get)]
+  /// The time the crawl started as seconds since the unix epoch.
+  pub start_time: f64,
+  #[pyo3(get)]
+  /// The time the crawl ended as seconds since the unix epoch.
+  pub end_time: f64,
+  #[pyo3(get)]
+  /// The duration of the crawl in seconds.
+  pub duration: f64,
+  #[pyo3(get)]
+  /// The amount of pages fetched.
+  pub pages_fetched: u32,
+  #[pyo3(get)]
+  /// The pages fetched that the report fell behind on. These are counted in pages_fetched but missing from the status codes, bytes downloaded, failed urls, and OpenAI usage.
+  pub pages_missed: u32,
+  #[pyo3(get)]
+  /// The amount of pages fetched by HTTP status code.
+  pub status_codes: HashMap<u16, u32>,
+  #[pyo3(get)]
+  /// The total bytes downloaded.
+  pub bytes_downloaded: u64,
+  #[pyo3(get)]
+  /// The links found that were not crawled due to robots.txt. Requires website.with_return_page_links to be set to true.
+  pub skipped_robots: u32,
+  #[pyo3(get)]
+  /// The links found that were not crawled due to robots.txt. Requires website.with_return_page_links to be set to true.
+  pub robots_blocked_urls: Vec<String>,
+  #[pyo3(get)]
+  /// The links found that were not crawled due to the blacklist or whitelist. Requires website.with_return_page_links to be set to true.
+  pub skipped_blacklist: u32,
+  #[pyo3(get)]
+  /// The links found that were not crawled as the website.with_budget limit of their path was reached. Requires website.with_return_page_links to be set to true.
+  pub skipped_budget: u32,
+  #[pyo3(get)]
+  /// The urls that failed with the reason.
+  pub failed_urls: Vec<(String, String)>,
+  #[pyo3(get)]
+  /// The OpenAI tokens used during the crawl.
+  pub ai_usage: AIUsage,
+}
+
+#[pymethods]
+impl CrawlReport {
+  fn __repr__(&self) -> String {
+    format!(
+      "CrawlReport(pages_fetched={}, pages_missed={}, failed={}, bytes_downloaded={}, duration={:.3})",
+      self.pages_fetched,
+      self.pages_missed,
+      self.failed_urls.len(),
+      self.bytes_downloaded,
+      self.duration
+    )
+  }
+}
+
+/// the current time as seconds since the unix epoch.
+fn now() -> f64 {
+  SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .map(|d| d.as_secs_f64())
+    .unwrap_or_default()
+}
+
+/// gathers the report while the pages are sent.
+#[derive(Default)]
+struct ReportCollector {
+  /// the report being built.
+  report: CrawlReport,
+  /// the links found on the pages.
+  discovered: HashSet<CaseInsensitiveString>,
+}
+
+impl ReportCollector {
+  /// record a page sent from the crawl.
+  fn record(&mut self, page: &spider::page::Page) {
+    let status_code = page.status_code;
+
+    self.report.pages_fetched += 1;
+    *self
+      .report
+      .status_codes
+      .entry(status_code.as_u16())
+      .or_default() += 1;
+    self.report.bytes_downloaded += match page.bytes_transferred {
+      Some(bytes) => bytes as u64,
+      _ => page.get_html_bytes_u8().len() as u64,
+    };
+
+    if page.error_status.is_some() || status_code.is_client_error() || status_code.is_server_error()
+    {
+      let reason = match page.error_status {
+        Some(ref error) => error.clone(),
+        _ => status_code
+          .canonical_reason()
+          .unwrap_or("request failed")
+          .to_string(),
+      };
+      self
+        .report
+        .failed_urls
+        .push((page.get_url().into(), reason));
+    }
+
+    if let Some(usage) = AIUsage::from_page(page) {
+      self.report.ai_usage.add(&usage);
+    }
+
+    if let Some(ref links) = page.page_links {
+      self.discovered.extend(links.iter().cloned());
+    }
+  }
+
+  /// record the pages skipped from falling behind the crawl.
+  fn missed(&mut self, skipped: u64) {
+    let skipped = u32::try_from(skipped).unwrap_or(u32::MAX);
+
+    self.report.pages_fetched = self.report.pages_fetched.saturating_add(skipped);
+    self.report.pages_missed = self.report.pages_missed.saturating_add(skipped);
+  }
+
+  /// complete the report and the link outcomes using the website state after the crawl.
+  fn finish(mut self, website: &spider::website::Website, outcomes: &LinkOutcomes) -> CrawlReport {
+    let visited = website.get_links();
+    let respect_robots_txt = website.configuration.respect_robots_txt;
+    let budget = budget_usage(website, &visited);
+    let mut skipped = Vec::new();
+
+    for link in self.discovered.iter().filter(|l| !visited.contains(*l)) {
+      if respect_robots_txt && !website.is_allowed_robots(link.as_ref()) {
+        self.report.skipped_robots += 1;
+        self
+          .report
+          .robots_blocked_urls
+          .push(link.as_ref().to_string());
+        skipped.push((link.as_ref().to_string(), "robots"));
+      } else if matches!(website.is_allowed_default(link), ProcessLinkStatus::Blocked) {
+        self.report.skipped_blacklist += 1;
+        skipped.push((link.as_ref().to_string(), "blacklist"));
+      } else if budget
+        .iter()
+        .any(|(path, limit, used)| used >= limit && in_budget_path(path, link.as_ref()))
+      {
+        self.report.skipped_budget += 1;
+        skipped.push((link.as_ref().to_string(), "budget"));
+      }
+    }
+
+    outcomes.finish(visited.iter().map(|link| link.as_ref()), skipped);
+
+    self.report.end_time = now();
+    self.report.duration = (self.report.end_time - self.report.start_time).max(0.0);
+    self.report
+  }
+}
+
+/// the link is counted against the budget of the path. The * path counts every link.
+fn in_budget_path(path: &str, link: &str) -> bool {
+  path == "*"
+    || spider::url::Url::parse(link).is_ok_and(|url| url.path().to_lowercase().starts_with(path))
+}
+
+/// the budget of the website with the path, the page limit, and the pages visited under the path.
+fn budget_usage(
+  website: &spider::website::Website,
+  visited: &HashSet<CaseInsensitiveString>,
+) -> Vec<(String, u32, u32)> {
+  match website.configuration.budget {
+    Some(ref budget) => budget
+      .iter()
+      .map(|(path, limit)| {
+        let path = path.as_ref().to_lowercase();
+        let used = visited
+          .iter()
+          .filter(|link| in_budget_path(&path, link.as_ref()))
+          .count();
+
+        (path, *limit, u32::try_from(used).unwrap_or(u32::MAX))
+      })
+      .collect(),
+    _ => Default::default(),
+  }
+}
+
+/// a task recording the pages sent from the website for the crawl report.
+pub struct ReportTask {
+  /// signal the crawl completed.
+  done: oneshot::Sender<()>,
+  /// the collecting task.
+  handle: JoinHandle<ReportCollector>,
+  /// the outcomes of the links found during the run.
+  outcomes: LinkOutcomes,
+}
+
+impl ReportTask {
+  /// subscribe to the website and start recording the report, the page timings, the link graph, and the link outcomes before the subscriber filters run. Must be called before the crawl starts.
+  pub fn spawn(website: &mut spider::website::Website, options: &PageOptions) -> Self {
+    let timings = options.timings.clone();
+    let graph = options.graph.clone();
+    let outcomes = options.outcomes.clone();
+    let crawled = outcomes.clone();
+
+    timings.attach(website);
+    graph.reset(website.get_url().inner());
+    outcomes.reset();
+
+    let mut rx2 = website
+      .subscribe(*BUFFER / 2)
+      .expect("sync feature should be enabled");
+    let (done, mut done_rx) = oneshot::channel::<()>();
+
+    let handle = pyo3_async_runtimes::tokio::get_runtime().spawn(async move {
+      let mut collector = ReportCollector::default();
+      collector.report.start_time = now();
+
+      loop {
+        select! {
+          res = rx2.recv() => match res {
+            Ok(page) => {
+              timings.complete(&page);
+              graph.record(&page);
+              crawled.crawled(page.get_url());
+              collector.record(&page);
+            }
+            Err(RecvError::Lagged(skipped)) => collector.missed(skipped),
+            Err(RecvError::Closed) => break,
+          },
+          _ = &mut done_rx => {
+            // record the pages still buffered after the crawl completes.
+            loop {
+              match rx2.try_recv() {
+                Ok(page) => {
+                  timings.complete(&page);
+                  graph.record(&page);
+                  crawled.crawled(page.get_url());
+                  collector.record(&page);
+                }
+                Err(TryRecvError::Lagged(skipped)) => collector.missed(skipped),
+                _ => break,
+              }
+            }
+            break;
+          }
+        }
+      }
+
+      collector
+    });
+
+    ReportTask {
+      done,
+      handle,
+      outcomes,
+    }
+  }
+
+  /// stop recording and build the report.
+  pub async fn finish(self, website: &spider::website::Website) -> CrawlReport {
+    let _ = self.done.send(());
+
+    match self.handle.await {
+      Ok(collector) => collector.finish(website, &self.outcomes),
+      _ => CrawlReport::default(),
+    }
+  }
+}
@@ -0,0 +1,192 @@
+use crate::links::base_url;
+use pyo3::prelude::*;
+use scraper::{Html, Selector};
+use serde::{Deserialize, Serialize};
+use spider::lazy_static::lazy_static;
+use spider::url::Url;
+use std::collections::HashSet;
+
+lazy_static! {
+  static ref IMAGE_SELECTOR: Selector =
+    Selector::parse("img, picture source[srcset], input[type=image][src]").unwrap();
+  static ref POSTER_SELECTOR: Selector = Selector::parse("video[poster]").unwrap();
+  static ref ICON_SELECTOR: Selector =
+    Selector::parse("link[rel~=icon][href], link[rel~=apple-touch-icon][href]").unwrap();
+  static ref SCRIPT_SELECTOR: Selector =
+    Selector::parse("script[src], link[rel=modulepreload][href]").unwrap();
+  static ref STYLESHEET_SELECTOR: Selector =
+    Selector::parse("link[rel~=stylesheet][href]").unwrap();
+  static ref FONT_SELECTOR: Selector = Selector::parse("link[as=font][href]").unwrap();
+  static ref STYLE_SELECTOR: Selector = Selector::parse("style").unwrap();
+  static ref MEDIA_SELECTOR: Selector = Selector::parse(
+    "video[src], audio[src], video source[src], audio source[src], video track[src], audio track[src]"
+  )
+  .unwrap();
+  static ref IFRAME_SELECTOR: Selector = Selector::parse("iframe[src], frame[src]").unwrap();
+}
+
+/// the font file extensions found in inline styles.
+const FONT_EXTENSIONS: [&str; 5] = [".woff2", ".woff", ".ttf", ".otf", ".eot"];
+
+/// the assets referenced by the page as absolute urls.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+#[pyclass]
+pub struct PageResources {
+  #[pyo3(get)]
+  /// The images including the srcset candidates, posters, and icons.
+  pub images: Vec<String>,
+  #[pyo3(get)]
+  /// The scripts.
+  pub scripts: Vec<String>,
+  #[pyo3(get)]
+  /// The stylesheets.
+  pub stylesheets: Vec<String>,
+  #[pyo3(get)]
+  /// The fonts preloaded or declared in inline styles.
+  pub fonts: Vec<String>,
+  #[pyo3(get)]
+  /// The video and audio sources and tracks.
+  pub media: Vec<String>,
+  #[pyo3(get)]
+  /// The iframes.
+  pub iframes: Vec<String>,
+}
+
+#[pymethods]
+impl PageResources {
+  fn __repr__(&self) -> String {
+    format!(
+      "PageResources(images={}, scripts={}, stylesheets={}, fonts={}, media={}, iframes={})",
+      self.images.len(),
+      self.scripts.len(),
+      self.stylesheets.len(),
+      self.fonts.len(),
+      self.media.len(),
+      self.iframes.len()
+    )
+  }
+}
+
+/// collects the unique absolute urls of a resource type.
+struct Collector<'a> {
+  /// the url to resolve the relative urls.
+  base_url: &'a Url,
+  /// the urls found in order.
+  urls: Vec<String>,
+  /// the urls found.
+  seen: HashSet<String>,
+}
+
+impl<'a> Collector<'a> {
+  /// a new collector.
+  fn new(base_url: &'a Url) -> Self {
+    Collector {
+      base_url,
+      urls: Vec::new(),
+      seen: HashSet::new(),
+    }
+  }
+
+  /// add the url resolved against the base url.
+  fn push(&mut self, value: &str) {
+    let value = value.trim();
+
+    if value.is_empty() || value.starts_with("data:") {
+      return;
+    }
+
+    if let Ok(mut url) = self.base_url.join(value) {
+      if matches!(url.scheme(), "http" | "https") {
+        url.set_fragment(None);
+        let url = url.to_string();
+
+        if self.seen.insert(url.clone()) {
+          self.urls.push(url);
+        }
+      }
+    }
+  }
+
+  /// add each candidate url of a srcset.
+  fn push_srcset(&mut self, srcset: &str) {
+    for candidate in srcset.split(',') {
+      if let Some(url) = candidate.split_whitespace().next() {
+        self.push(url);
+      }
+    }
+  }
+
+  /// add the attributes of the elements matching the selector.
+  fn collect(mut self, document: &Html, selector: &Selector, attributes: &[&str]) -> Self {
+    for element in document.select(selector) {
+      let value = element.value();
+
+      for attribute in attributes {
+        if let Some(url) = value.attr(attribute) {
+          if attribute.ends_with("srcset") {
+            self.push_srcset(url);
+          } else {
+            self.push(url);
+          }
+        }
+      }
+    }
+
+    self
+  }
+}
+
+/// get the font urls declared with url() in the css.
+fn css_font_urls(css: &str) -> impl Iterator<Item = &str> {
+  css.split("url(").skip(1).filter_map(|rest| {
+    let url = rest[..rest.find(')')?].trim().trim_matches(['"', '\'']);
+    let path = url.split(['?', '#']).next().unwrap_or_default();
+    let path = path.to_ascii_lowercase();
+
+    FONT_EXTENSIONS
+      .iter()
+      .any(|extension| path.ends_with(extension))
+      .then_some(url)
+  })
+}
+
+/// extract the resources referenced by the document.
+pub fn extract_resources(document: &Html, url: &str) -> PageResources {
+  let page_url = match Url::parse(url) {
+    Ok(page_url) => page_url,
+    _ => return Default::default(),
+  };
+  let base_url = base_url(document, &page_url);
+
+  let mut fonts = Collector::new(&base_url).collect(document, &FONT_SELECTOR, &["href"]);
+
+  for style in document.select(&STYLE_SELECTOR) {
+    let css = style.text().collect::<String>();
+
+    for font in css_font_urls(&css) {
+      fonts.push(font);
+    }
+  }
+
+  PageResources {
+    images: Collector::new(&base_url)
+      .collect(document, &IMAGE_SELECTOR, &["src", "srcset", "data-src"])
+      .collect(document, &POSTER_SELECTOR, &["poster"])
+      .collect(document, &ICON_SELECTOR, &["href"])
+      .urls,
+    scripts: Collector::new(&base_url)
+      .collect(document, &SCRIPT_SELECTOR, &["src", "href"])
+      .urls,
+    stylesheets: Collector::new(&base_url)
+      .collect(document, &STYLESHEET_SELECTOR, &["href"])
+      .urls,
+    fonts: fonts.urls,
+    media: Collector::new(&base_url)
+      .collect(document, &MEDIA_SELECTOR, &["src"])
+      .urls,
+    iframes: Collector::new(&base_url)
+      .collect(document, &IFRAME_SELECTOR, &["src"])
+      .urls,
+  }
+}
@@ -0,0 +1,56 @@
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+use serde::{Deserialize, Serialize};
+
+/// the screenshot of the page taken with chrome.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[pyclass]
+pub struct Screenshot {
+  /// The image bytes.
+  #[serde(with = "crate::utils::base64_bytes")]
+  pub data: Vec<u8>,
+  #[pyo3(get)]
+  /// The image format: png, jpeg, or webp.
+  pub format: String,
+}
+
+impl Screenshot {
+  /// a new screenshot detecting the format from the image bytes.
+  pub fn new(data: Vec<u8>) -> Self {
+    let format = if data.starts_with(b"\x89PNG") {
+      "png"
+    } else if data.starts_with(b"\xFF\xD8") {
+      "jpeg"
+    } else if data.len() > 12 && data.starts_with(b"RIFF") && &data[8..12] == b"WEBP" {
+      "webp"
+    } else {
+      "png"
+    };
+
+    Screenshot {
+      data,
+      format: format.into(),
+    }
+  }
+}
+
+#[pymethods]
+impl Screenshot {
+  /// the image bytes.
+  #[getter]
+  pub fn data<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+    PyBytes::new(py, &self.data)
+  }
+
+  fn __len__(&self) -> usize {
+    self.data.len()
+  }
+
+  fn __repr__(&self) -> String {
+    format!(
+      "Screenshot(format={:?}, size={})",
+      self.format,
+      self.data.len()
+    )
+  }
+}
@@ -1,4 +1,5 @@
 use crate::new_page;
+use crate::npage::PageOptions;
 use crate::NWebsite;
 use crate::BUFFER;
 
@@ -9,11 +10,13 @@ pub async fn crawl(url: String, raw_content: Option<bool>) -> NWebsite {
     .subscribe(*BUFFER / 2)
     .expect("sync feature should be enabled");
   let (tx, mut rx) = spider::tokio::sync::mpsc::channel(*BUFFER);
-  let raw_content = raw_content.unwrap_or_default();
+  let page_options = PageOptions::new(raw_content.unwrap_or_default());
+
+  page_options.graph.reset(&url);
 
   spider::tokio::spawn(async move {
     while let Ok(res) = rx2.recv().await {
-      if let Err(_) = tx.send(new_page(&res, raw_content)).await {
+      if let Err(_) = tx.send(new_page(&res, &page_options)).await {
         println!("receiver dropped");
         return;
       }
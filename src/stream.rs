@@ -0,0 +1,99 @@
+use crate::handle::CrawlHandle;
+use crate::NPage;
+use pyo3::exceptions::PyStopAsyncIteration;
+use pyo3::prelude::*;
+use spider::tokio::sync::mpsc::Receiver;
+use spider::tokio::sync::Mutex;
+use std::sync::Arc;
+
+/// an async iterator of the pages found during a crawl.
+#[pyclass]
+pub struct PageStream {
+  /// the pages sent from the crawl.
+  receiver: Arc<Mutex<Receiver<NPage>>>,
+  /// the crawl feeding the stream.
+  crawl_handle: CrawlHandle,
+}
+
+impl PageStream {
+  /// a new stream of pages.
+  pub fn new(receiver: Receiver<NPage>, crawl_handle: CrawlHandle) -> Self {
+    PageStream {
+      receiver: Arc::new(Mutex::new(receiver)),
+      crawl_handle,
+    }
+  }
+}
+
+#[pymethods]
+impl PageStream {
+  fn __aiter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+    slf
+  }
+
+  fn __anext__<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+    let receiver = self.receiver.clone();
+
+    pyo3_async_runtimes::tokio::future_into_py(py, async move {
+      match receiver.lock().await.recv().await {
+        Some(page) => Ok(page),
+        _ => Err(PyStopAsyncIteration::new_err("crawl finished")),
+      }
+    })
+  }
+
+  /// stop the crawl feeding the stream.
+  pub fn stop(&self) {
+    self.crawl_handle.cancel();
+  }
+}
+
+impl Drop for PageStream {
+  fn drop(&mut self) {
+    self.crawl_handle.cancel();
+  }
+}
+
+/// a blocking iterator of the pages found during a crawl.
+#[pyclass]
+pub struct PageIter {
+  /// the pages sent from the crawl.
+  receiver: Arc<Mutex<Receiver<NPage>>>,
+  /// the crawl feeding the iterator.
+  crawl_handle: CrawlHandle,
+}
+
+impl PageIter {
+  /// a new iterator of pages.
+  pub fn new(receiver: Receiver<NPage>, crawl_handle: CrawlHandle) -> Self {
+    PageIter {
+      receiver: Arc::new(Mutex::new(receiver)),
+      crawl_handle,
+    }
+  }
+}
+
+#[pymethods]
+impl PageIter {
+  fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+    slf
+  }
+
+  fn __next__(&self, py: Python<'_>) -> Option<NPage> {
+    let receiver = &self.receiver;
+
+    // release the GIL while waiting on the crawl, stop can be called from another thread meanwhile.
+    py.allow_threads(|| receiver.blocking_lock().blocking_recv())
+  }
+
+  /// stop the crawl feeding the iterator.
+  pub fn stop(&self) {
+    self.crawl_handle.cancel();
+  }
+}
+
+impl Drop for PageIter {
+  fn drop(&mut self) {
+    self.crawl_handle.cancel();
+  }
+}
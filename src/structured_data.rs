@@ -0,0 +1,230 @@
+use crate::links::base_url;
+use scraper::{ElementRef, Html, Selector};
+use serde_json::{Map, Value as JsonValue};
+use spider::lazy_static::lazy_static;
+use spider::url::Url;
+
+lazy_static! {
+  static ref JSON_LD_SELECTOR: Selector =
+    Selector::parse("script[type=\"application/ld+json\"]").unwrap();
+  static ref MICRODATA_SELECTOR: Selector = Selector::parse("[itemscope]:not([itemprop])").unwrap();
+  static ref RDFA_SELECTOR: Selector = Selector::parse("[typeof]:not([property])").unwrap();
+}
+
+/// the attributes marking the items and properties of a syntax.
+struct Syntax {
+  /// the attribute starting an item.
+  scope: &'static str,
+  /// the attribute naming a property.
+  property: &'static str,
+  /// the attribute holding the item types.
+  types: &'static str,
+  /// the attribute holding the item id.
+  id: &'static str,
+  /// the values are read with the RDFa rules.
+  rdfa: bool,
+}
+
+/// the microdata attributes.
+const MICRODATA: Syntax = Syntax {
+  scope: "itemscope",
+  property: "itemprop",
+  types: "itemtype",
+  id: "itemid",
+  rdfa: false,
+};
+
+/// the RDFa attributes.
+const RDFA: Syntax = Syntax {
+  scope: "typeof",
+  property: "property",
+  types: "typeof",
+  id: "resource",
+  rdfa: true,
+};
+
+/// get the JSON-LD blocks of the document. Invalid blocks are skipped.
+fn json_ld(document: &Html) -> Vec<JsonValue> {
+  let mut blocks = Vec::new();
+
+  for script in document.select(&JSON_LD_SELECTOR) {
+    let text = script.text().collect::<String>();
+
+    match serde_json::from_str::<JsonValue>(text.trim()) {
+      Ok(JsonValue::Array(items)) => blocks.extend(items),
+      Ok(value) => blocks.push(value),
+      _ => (),
+    }
+  }
+
+  blocks
+}
+
+/// the vocabulary of the element or its nearest ancestor.
+fn rdfa_vocab(element: ElementRef<'_>) -> Option<&str> {
+  std::iter::once(element)
+    .chain(element.ancestors().filter_map(ElementRef::wrap))
+    .find_map(|element| element.value().attr("vocab"))
+}
+
+impl Syntax {
+  /// the types of the item expanded with the RDFa vocabulary.
+  fn item_types(&self, element: ElementRef<'_>) -> Vec<JsonValue> {
+    let vocab = if self.rdfa { rdfa_vocab(element) } else { None };
+
+    element
+      .value()
+      .attr(self.types)
+      .unwrap_or_default()
+      .split_whitespace()
+      .map(|kind| match vocab {
+        Some(vocab) if !kind.contains(':') => JsonValue::String(format!("{vocab}{kind}")),
+        _ => JsonValue::String(kind.to_string()),
+      })
+      .collect()
+  }
+
+  /// the value of a property element.
+  fn property_value(&self, element: ElementRef<'_>, base_url: &Url) -> JsonValue {
+    if element.value().attr(self.scope).is_some() {
+      return self.item(element, base_url);
+    }
+
+    let value = element.value();
+    let resolve = |url: &str| match base_url.join(url.trim()) {
+      Ok(url) => url.to_string(),
+      _ => url.to_string(),
+    };
+    let text = || {
+      element
+        .text()
+        .flat_map(|text| text.split_whitespace())
+        .collect::<Vec<&str>>()
+        .join(" ")
+    };
+
+    let text = if self.rdfa {
+      match (
+        value.attr("content"),
+        value.attr("resource"),
+        value.attr("href"),
+        value.attr("src"),
+      ) {
+        (Some(content), ..) => content.to_string(),
+        (_, Some(url), ..) | (_, _, Some(url), _) | (.., Some(url)) => resolve(url),
+        _ => text(),
+      }
+    } else {
+      match value.name() {
+        "meta" => value.attr("content").unwrap_or_default().to_string(),
+        "audio" | "embed" | "iframe" | "img" | "source" | "track" | "video" => {
+          resolve(value.attr("src").unwrap_or_default())
+        }
+        "a" | "area" | "link" => resolve(value.attr("href").unwrap_or_default()),
+        "object" => resolve(value.attr("data").unwrap_or_default()),
+        "data" | "meter" => value.attr("value").unwrap_or_default().to_string(),
+        "time" => match value.attr("datetime") {
+          Some(datetime) => datetime.to_string(),
+          _ => text(),
+        },
+        _ => text(),
+      }
+    };
+
+    JsonValue::String(text)
+  }
+
+  /// add the properties under the element without entering the nested items.
+  fn collect_properties(
+    &self,
+    element: ElementRef<'_>,
+    base_url: &Url,
+    properties: &mut Map<String, JsonValue>,
+  ) {
+    for child in element.children().filter_map(ElementRef::wrap) {
+      let value = child.value();
+
+      if let Some(names) = value.attr(self.property) {
+        let property = self.property_value(child, base_url);
+
+        for name in names.split_whitespace() {
+          if let JsonValue::Array(values) = properties
+            .entry(name.to_string())
+            .or_insert_with(|| JsonValue::Array(Vec::new()))
+          {
+            values.push(property.clone());
+          }
+        }
+      }
+
+      if value.attr(self.scope).is_none() {
+        self.collect_properties(child, base_url, properties);
+      }
+    }
+  }
+
+  /// the item of the element with the types, id, and properties.
+  fn item(&self, element: ElementRef<'_>, base_url: &Url) -> JsonValue {
+    let mut item = Map::new();
+    let mut properties = Map::new();
+
+    self.collect_properties(element, base_url, &mut properties);
+
+    let types = self.item_types(element);
+
+    if !types.is_empty() {
+      item.insert("type".into(), JsonValue::Array(types));
+    }
+
+    if let Some(id) = element.value().attr(self.id) {
+      item.insert("id".into(), JsonValue::String(id.to_string()));
+    }
+
+    item.insert("properties".into(), JsonValue::Object(properties));
+
+    JsonValue::Object(item)
+  }
+}
+
+/// extract the JSON-LD blocks, microdata items, and RDFa items of the document.
+/// The RDFa properties outside of a typed item are grouped in an item without a type.
+pub fn extract_structured_data(document: &Html, url: &str) -> JsonValue {
+  let page_url = Url::parse(url).ok();
+  let mut data = Map::new();
+
+  data.insert("json_ld".into(), JsonValue::Array(json_ld(document)));
+
+  let (microdata, rdfa) = match page_url {
+    Some(ref page_url) => {
+      let base_url = base_url(document, page_url);
+      let microdata = document
+        .select(&MICRODATA_SELECTOR)
+        .map(|element| MICRODATA.item(element, &base_url))
+        .collect::<Vec<JsonValue>>();
+      let mut rdfa = Vec::new();
+      let mut properties = Map::new();
+
+      RDFA.collect_properties(document.root_element(), &base_url, &mut properties);
+
+      if !properties.is_empty() {
+        let mut item = Map::new();
+        item.insert("properties".into(), JsonValue::Object(properties));
+        rdfa.push(JsonValue::Object(item));
+      }
+
+      rdfa.extend(
+        document
+          .select(&RDFA_SELECTOR)
+          .map(|element| RDFA.item(element, &base_url)),
+      );
+
+      (microdata, rdfa)
+    }
+    _ => Default::default(),
+  };
+
+  data.insert("microdata".into(), JsonValue::Array(microdata));
+  data.insert("rdfa".into(), JsonValue::Array(rdfa));
+
+  JsonValue::Object(data)
+}
@@ -0,0 +1,103 @@
+use crate::utils::url_key;
+use spider::reqwest::{Request, Response};
+use spider::reqwest_middleware::{ClientBuilder, Middleware, Next};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+/// the timing of a request recorded by the http client.
+#[derive(Debug, Clone)]
+struct RequestTiming {
+  /// the seconds until the response headers were received.
+  ttfb: f64,
+  /// the http version of the response ex: HTTP/1.1, HTTP/2.0.
+  http_version: String,
+}
+
+/// the timings of the pages fetched during a run keyed by url.
+#[derive(Debug, Default, Clone)]
+pub struct FetchTimings {
+  /// the requests recorded by the http client.
+  requests: Arc<Mutex<HashMap<String, RequestTiming>>>,
+  /// the seconds from the start of the request until the page was sent from the crawl.
+  durations: Arc<Mutex<HashMap<String, f64>>>,
+}
+
+impl FetchTimings {
+  /// forget the timings and record the requests of the next run with the http client of the website.
+  /// Must be called before the crawl starts. The pages rendered with chrome are not fetched with the http client.
+  pub fn attach(&self, website: &mut spider::website::Website) {
+    if let Ok(mut requests) = self.requests.lock() {
+      requests.clear();
+    }
+    if let Ok(mut durations) = self.durations.lock() {
+      durations.clear();
+    }
+
+    let client = ClientBuilder::from_client(website.configure_http_client())
+      .with(TimingMiddleware {
+        requests: self.requests.clone(),
+      })
+      .build();
+
+    website.set_http_client(client);
+  }
+
+  /// record the duration of the page. The first record of a url is kept so the page should be recorded as soon as it is sent.
+  pub fn complete(&self, res: &spider::page::Page) -> f64 {
+    let mut durations = match self.durations.lock() {
+      Ok(durations) => durations,
+      Err(e) => e.into_inner(),
+    };
+
+    *durations
+      .entry(url_key(res.get_url()))
+      .or_insert_with(|| res.get_duration_elapsed().as_secs_f64())
+  }
+
+  /// the time to first byte and the http version of the request of the url.
+  pub fn request(&self, url: &str) -> (Option<f64>, Option<String>) {
+    let requests = match self.requests.lock() {
+      Ok(requests) => requests,
+      Err(e) => e.into_inner(),
+    };
+
+    match requests.get(&url_key(url)) {
+      Some(timing) => (Some(timing.ttfb), Some(timing.http_version.clone())),
+      _ => (None, None),
+    }
+  }
+}
+
+/// records the time to first byte and the http version of the requests.
+struct TimingMiddleware {
+  /// the requests recorded keyed by url.
+  requests: Arc<Mutex<HashMap<String, RequestTiming>>>,
+}
+
+#[async_trait::async_trait]
+impl Middleware for TimingMiddleware {
+  async fn handle(
+    &self,
+    req: Request,
+    extensions: &mut http::Extensions,
+    next: Next<'_>,
+  ) -> spider::reqwest_middleware::Result<Response> {
+    let url = req.url().to_string();
+    let start = Instant::now();
+    let res = next.run(req, extensions).await;
+
+    if let Ok(ref response) = res {
+      let timing = RequestTiming {
+        ttfb: start.elapsed().as_secs_f64(),
+        http_version: format!("{:?}", response.version()),
+      };
+
+      if let Ok(mut requests) = self.requests.lock() {
+        requests.insert(url, timing);
+      }
+    }
+
+    res
+  }
+}
@@ -0,0 +1,570 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::PyResult;
+use scraper::{ElementRef, Html, Node};
+use serde::{Deserialize, Serialize};
+
+/// the format of the content sent to python.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReturnFormat {
+  /// the html decoded as UTF-8.
+  #[default]
+  Html,
+  /// the bytes of the resource without decoding.
+  Raw,
+  /// the html converted to markdown.
+  Markdown,
+  /// the html converted to plain text.
+  Text,
+}
+
+impl ReturnFormat {
+  /// parse the return format from python.
+  pub fn parse(format: &str) -> PyResult<Self> {
+    match format.to_ascii_lowercase().as_str() {
+      "html" => Ok(ReturnFormat::Html),
+      "raw" => Ok(ReturnFormat::Raw),
+      "markdown" => Ok(ReturnFormat::Markdown),
+      "text" => Ok(ReturnFormat::Text),
+      f => Err(PyValueError::new_err(format!(
+        "Unknown return format {f}, expected html, raw, markdown, or text"
+      ))),
+    }
+  }
+}
+
+/// the elements skipped as they hold no readable content.
+const SKIPPED_TAGS: [&str; 13] = [
+  "script", "style", "noscript", "template", "head", "nav", "footer", "aside", "svg", "canvas",
+  "iframe", "form", "button",
+];
+
+/// the landmark roles of navigation boilerplate.
+const SKIPPED_ROLES: [&str; 3] = ["navigation", "banner", "contentinfo"];
+
+/// the characters escaped in the markdown text.
+const MARKDOWN_CHARS: [char; 8] = ['\\', '`', '*', '_', '[', ']', '<', '~'];
+
+/// converts the document into markdown or text.
+struct Writer<'a> {
+  /// the output.
+  out: String,
+  /// write markdown syntax instead of plain text.
+  markdown: bool,
+  /// the url to resolve relative links.
+  base_url: Option<&'a spider::url::Url>,
+  /// the lists being written with the ordered flag and the item count.
+  lists: Vec<(bool, usize)>,
+  /// writing the cell of a table.
+  in_table: bool,
+}
+
+impl<'a> Writer<'a> {
+  /// a new writer.
+  fn new(markdown: bool, base_url: Option<&'a spider::url::Url>) -> Self {
+    Writer {
+      out: String::new(),
+      markdown,
+      base_url,
+      lists: Vec::new(),
+      in_table: false,
+    }
+  }
+
+  /// end the current block with a blank line.
+  fn block(&mut self) {
+    let trimmed = self.out.trim_end_matches([' ', '\n']).len();
+    self.out.truncate(trimmed);
+
+    if !self.out.is_empty() {
+      self.out.push_str("\n\n");
+    }
+  }
+
+  /// end the current line.
+  fn line(&mut self) {
+    let trimmed = self.out.trim_end_matches(' ').len();
+    self.out.truncate(trimmed);
+
+    if !self.out.is_empty() && !self.out.ends_with('\n') {
+      self.out.push('\n');
+    }
+  }
+
+  /// is the output at the start of a line.
+  fn at_line_start(&self) -> bool {
+    self.out.is_empty() || self.out.ends_with('\n')
+  }
+
+  /// write text collapsing the whitespace and escaping the markdown syntax.
+  fn text(&mut self, text: &str) {
+    self.write_text(text, self.markdown);
+  }
+
+  /// write text collapsing the whitespace.
+  fn write_text(&mut self, text: &str, escape: bool) {
+    let starts_with_space = text.starts_with(char::is_whitespace);
+    let ends_with_space = text.ends_with(char::is_whitespace);
+    let mut words = text.split_whitespace().peekable();
+
+    if words.peek().is_none() {
+      if starts_with_space && !self.at_line_start() && !self.out.ends_with(' ') {
+        self.out.push(' ');
+      }
+      return;
+    }
+
+    if starts_with_space && !self.at_line_start() && !self.out.ends_with(' ') {
+      self.out.push(' ');
+    }
+
+    let mut first = true;
+
+    for word in words {
+      if !first {
+        self.out.push(' ');
+      }
+      if escape {
+        self.escape(word);
+      } else {
+        self.out.push_str(word);
+      }
+      first = false;
+    }
+
+    if ends_with_space {
+      self.out.push(' ');
+    }
+  }
+
+  /// write a word escaping the characters read as markdown syntax.
+  fn escape(&mut self, word: &str) {
+    if self.at_line_start() {
+      let digits = word.trim_start_matches(|c: char| c.is_ascii_digit());
+
+      if word.starts_with(['#', '>', '-', '+', '='])
+        || (digits.len() < word.len() && digits.starts_with(['.', ')']))
+      {
+        let (prefix, rest) = word.split_at(word.len() - digits.len());
+        self.out.push_str(prefix);
+        self.out.push('\\');
+        self.escape_inline(rest);
+        return;
+      }
+    }
+
+    self.escape_inline(word);
+  }
+
+  /// write a word escaping the inline markdown syntax.
+  fn escape_inline(&mut self, word: &str) {
+    for c in word.chars() {
+      if MARKDOWN_CHARS.contains(&c) {
+        self.out.push('\\');
+      }
+      self.out.push(c);
+    }
+  }
+
+  /// resolve a url against the page.
+  fn resolve(&self, href: &str) -> String {
+    match self.base_url.and_then(|base| base.join(href).ok()) {
+      Some(url) => url.to_string(),
+      _ => href.to_string(),
+    }
+  }
+
+  /// write the children of the element.
+  fn children(&mut self, element: ElementRef<'_>) {
+    for child in element.children() {
+      match child.value() {
+        Node::Text(text) => self.text(text),
+        Node::Element(_) => {
+          if let Some(child) = ElementRef::wrap(child) {
+            self.element(child);
+          }
+        }
+        _ => (),
+      }
+    }
+  }
+
+  /// write the children of the element into a new writer.
+  fn render(&self, element: ElementRef<'_>) -> String {
+    let mut writer = Writer::new(self.markdown, self.base_url);
+    writer.in_table = self.in_table;
+    writer.children(element);
+    writer.out.trim().to_string()
+  }
+
+  /// write an element.
+  fn element(&mut self, element: ElementRef<'_>) {
+    let value = element.value();
+    let name = value.name();
+
+    if SKIPPED_TAGS.contains(&name)
+      || value
+        .attr("role")
+        .is_some_and(|role| SKIPPED_ROLES.contains(&role))
+      || value.attr("hidden").is_some()
+    {
+      return;
+    }
+
+    match name {
+      "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+        self.block();
+        if self.markdown {
+          let level = name[1..].parse::<usize>().unwrap_or(1);
+          self.out.push_str(&"#".repeat(level));
+          self.out.push(' ');
+        }
+        self.children(element);
+        self.block();
+      }
+      "p" | "div" | "section" | "article" | "main" | "header" | "figure" | "figcaption"
+      | "address" | "details" | "summary" | "dl" => {
+        self.block();
+        self.children(element);
+        self.block();
+      }
+      "dt" | "dd" => {
+        self.line();
+        self.children(element);
+        self.line();
+      }
+      "br" => {
+        if self.markdown {
+          self.out.push_str("  ");
+        }
+        self.out.push('\n');
+      }
+      "hr" => {
+        self.block();
+        if self.markdown {
+          self.out.push_str("---");
+        }
+        self.block();
+      }
+      "strong" | "b" if self.markdown => self.wrap_inline(element, "**"),
+      "em" | "i" if self.markdown => self.wrap_inline(element, "*"),
+      "del" | "s" if self.markdown => self.wrap_inline(element, "~~"),
+      "code" if self.markdown => {
+        let code = element.text().collect::<String>();
+        self.text_boundary();
+        self.out.push('`');
+        self.out.push_str(code.trim());
+        self.out.push('`');
+      }
+      "pre" => {
+        let code = element.text().collect::<String>();
+        self.block();
+        if self.markdown {
+          self.out.push_str("```\n");
+          self.out.push_str(code.trim_end());
+          self.out.push_str("\n```");
+        } else {
+          self.out.push_str(code.trim_end());
+        }
+        self.block();
+      }
+      "a" => {
+        let href = value.attr("href").unwrap_or_default().trim();
+        let text = self.render(element);
+
+        if !self.markdown
+          || href.is_empty()
+          || href.starts_with('#')
+          || href.starts_with("javascript:")
+        {
+          self.write_text(&text, false);
+        } else if !text.is_empty() {
+          self.text_boundary();
+          self.out.push('[');
+          self.out.push_str(&text);
+          self.out.push_str("](");
+          self.out.push_str(&self.resolve(href));
+          self.out.push(')');
+        }
+      }
+      "img" => {
+        let alt = value.attr("alt").unwrap_or_default().trim();
+
+        if self.markdown {
+          if let Some(src) = value.attr("src") {
+            self.text_boundary();
+            self.out.push_str("![");
+            self.out.push_str(alt);
+            self.out.push_str("](");
+            self.out.push_str(&self.resolve(src.trim()));
+            self.out.push(')');
+          }
+        } else if !alt.is_empty() {
+          self.text(alt);
+        }
+      }
+      "ul" | "ol" => {
+        if self.lists.is_empty() {
+          self.block();
+        } else {
+          self.line();
+        }
+        self.lists.push((name == "ol", 0));
+        self.children(element);
+        self.lists.pop();
+        if self.lists.is_empty() {
+          self.block();
+        } else {
+          self.line();
+        }
+      }
+      "li" => {
+        self.line();
+        let depth = self.lists.len().max(1);
+        self.out.push_str(&"  ".repeat(depth - 1));
+
+        match self.lists.last_mut() {
+          Some((true, count)) => {
+            *count += 1;
+            self.out.push_str(&format!("{}. ", count));
+          }
+          _ => self.out.push_str("- "),
+        }
+
+        self.children(element);
+        self.line();
+      }
+      "blockquote" => {
+        let quote = self.render(element);
+        self.block();
+        for line in quote.lines() {
+          if self.markdown {
+            self.out.push_str("> ");
+          }
+          self.out.push_str(line);
+          self.out.push('\n');
+        }
+        self.block();
+      }
+      "table" => self.table(element),
+      _ => self.children(element),
+    }
+  }
+
+  /// separate inline syntax from the previous word.
+  fn text_boundary(&mut self) {
+    if !self.at_line_start()
+      && !self.out.ends_with(' ')
+      && !self.out.ends_with(['(', '[', '*', '_', '~'])
+    {
+      self.out.push(' ');
+    }
+  }
+
+  /// wrap the children of the element with inline markdown syntax.
+  fn wrap_inline(&mut self, element: ElementRef<'_>, marker: &str) {
+    let text = self.render(element);
+
+    if !text.is_empty() {
+      self.text_boundary();
+      self.out.push_str(marker);
+      self.out.push_str(&text);
+      self.out.push_str(marker);
+    }
+  }
+
+  /// write a table as a markdown table or tab separated text. The tables nested in a cell are written as text.
+  fn table(&mut self, element: ElementRef<'_>) {
+    let mut cell_writer = Writer::new(self.markdown, self.base_url);
+    let mut rows: Vec<Vec<String>> = Vec::new();
+
+    cell_writer.in_table = true;
+
+    for row in table_rows(element) {
+      let cells = row
+        .children()
+        .filter_map(ElementRef::wrap)
+        .filter(|e| matches!(e.value().name(), "td" | "th"))
+        .map(|cell| cell_writer.render(cell).replace('\n', " "))
+        .collect::<Vec<String>>();
+
+      if !cells.is_empty() {
+        rows.push(cells);
+      }
+    }
+
+    if rows.is_empty() {
+      return;
+    }
+
+    if self.in_table {
+      for row in rows {
+        self.text_boundary();
+        self.write_text(&row.join(" "), false);
+      }
+      return;
+    }
+
+    self.block();
+
+    if self.markdown {
+      let columns = rows.iter().map(|r| r.len()).max().unwrap_or_default();
+
+      for (i, row) in rows.iter().enumerate() {
+        self.out.push('|');
+        for c in 0..columns {
+          self.out.push(' ');
+          self.out.push_str(
+            &row
+              .get(c)
+              .map(|s| s.replace('|', "\\|"))
+              .unwrap_or_default(),
+          );
+          self.out.push_str(" |");
+        }
+        self.out.push('\n');
+
+        if i == 0 {
+          self.out.push('|');
+          self.out.push_str(&" --- |".repeat(columns));
+          self.out.push('\n');
+        }
+      }
+    } else {
+      for row in rows {
+        self.out.push_str(&row.join("\t"));
+        self.out.push('\n');
+      }
+    }
+
+    self.block();
+  }
+}
+
+/// the rows of the table without the rows of the nested tables.
+fn table_rows(table: ElementRef<'_>) -> Vec<ElementRef<'_>> {
+  let mut rows = Vec::new();
+
+  for child in table.children().filter_map(ElementRef::wrap) {
+    match child.value().name() {
+      "tr" => rows.push(child),
+      "thead" | "tbody" | "tfoot" => rows.extend(
+        child
+          .children()
+          .filter_map(ElementRef::wrap)
+          .filter(|e| e.value().name() == "tr"),
+      ),
+      _ => (),
+    }
+  }
+
+  rows
+}
+
+/// convert the document.
+fn convert(document: &Html, url: &str, markdown: bool) -> String {
+  let base_url = spider::url::Url::parse(url).ok();
+  let mut writer = Writer::new(markdown, base_url.as_ref());
+
+  writer.children(document.root_element());
+  writer.out.trim().to_string()
+}
+
+/// convert the document to markdown resolving the links against the url.
+pub fn to_markdown(document: &Html, url: &str) -> String {
+  convert(document, url, true)
+}
+
+/// convert the document to plain text.
+pub fn to_text(document: &Html) -> String {
+  convert(document, "", false)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn markdown(html: &str) -> String {
+    to_markdown(&Html::parse_document(html), "https://example.com/docs/")
+  }
+
+  #[test]
+  fn headings() {
+    assert_eq!(
+      markdown("<h1>Title</h1><h3>Section</h3><p>Body</p>"),
+      "# Title\n\n### Section\n\nBody"
+    );
+  }
+
+  #[test]
+  fn lists() {
+    assert_eq!(
+      markdown("<ul><li>one</li><li>two</li></ul><ol><li>first</li><li>second</li></ol>"),
+      "- one\n- two\n\n1. first\n2. second"
+    );
+  }
+
+  #[test]
+  fn nested_lists() {
+    assert_eq!(
+      markdown("<ul><li>one<ul><li>inner</li></ul></li><li>two</li></ul>"),
+      "- one\n  - inner\n- two"
+    );
+  }
+
+  #[test]
+  fn tables() {
+    assert_eq!(
+      markdown("<table><thead><tr><th>a</th><th>b</th></tr></thead><tbody><tr><td>1</td><td>2 | 3</td></tr></tbody></table>"),
+      "| a | b |\n| --- | --- |\n| 1 | 2 \\| 3 |"
+    );
+  }
+
+  #[test]
+  fn nested_tables() {
+    assert_eq!(
+      markdown(
+        "<table><tr><td>outer</td><td><table><tr><td>inner</td></tr></table></td></tr></table>"
+      ),
+      "| outer | inner |\n| --- | --- |"
+    );
+  }
+
+  #[test]
+  fn links() {
+    assert_eq!(
+      markdown(
+        r#"<p>See <a href="guide">the guide</a> and <a href="https://spider.cloud">spider</a>.</p>"#
+      ),
+      "See [the guide](https://example.com/docs/guide) and [spider](https://spider.cloud/)."
+    );
+  }
+
+  #[test]
+  fn escapes_text() {
+    assert_eq!(
+      markdown("<p>a *b* [c] snake_case</p><p># not a heading</p><p>1. not a list</p>"),
+      "a \\*b\\* \\[c\\] snake\\_case\n\n\\# not a heading\n\n1\\. not a list"
+    );
+  }
+
+  #[test]
+  fn skips_boilerplate() {
+    assert_eq!(
+      markdown(
+        r#"<head><title>t</title><style>p{}</style></head><body><nav>menu</nav><div role="banner">banner</div><main><p>content</p><script>x()</script></main><footer>footer</footer></body>"#
+      ),
+      "content"
+    );
+  }
+
+  #[test]
+  fn text() {
+    assert_eq!(
+      to_text(&Html::parse_document(
+        "<h2>Title</h2><p>a *b* <a href=\"/x\">link</a></p>"
+      )),
+      "Title\n\na *b* link"
+    );
+  }
+}
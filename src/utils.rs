@@ -1,9 +1,19 @@
 use pyo3::types::PyAnyMethods;
+use pyo3::types::PyBytesMethods;
 use pyo3::types::PyDictMethods;
-use pyo3::types::{PyAny, PyDict, PyList};
-use pyo3::{Bound, PyResult};
+use pyo3::types::PyListMethods;
+use pyo3::types::{PyAny, PyBytes, PyDict, PyFrozenSet, PyList, PySet, PyTuple};
+use pyo3::{Bound, IntoPyObjectExt, PyObject, PyResult, Python};
 use serde_json::Value as JsonValue;
 
+/// the url parsed and serialized so the same url written differently matches ex: a missing trailing slash.
+pub fn url_key(url: &str) -> String {
+  match spider::url::Url::parse(url) {
+    Ok(url) => url.into(),
+    _ => url.into(),
+  }
+}
+
 /// convert pyobject to json value
 pub fn pyobj_to_json_value(obj: &Bound<PyAny>) -> PyResult<JsonValue> {
   // Handle None
@@ -23,11 +33,15 @@ pub fn pyobj_to_json_value(obj: &Bound<PyAny>) -> PyResult<JsonValue> {
     }
   } else if let Ok(val) = obj.extract::<&str>() {
     Ok(JsonValue::String(val.to_string()))
-  } else if let Ok(list) = obj.downcast::<PyList>() {
+  } else if obj.is_instance_of::<PyList>()
+    || obj.is_instance_of::<PyTuple>()
+    || obj.is_instance_of::<PySet>()
+    || obj.is_instance_of::<PyFrozenSet>()
+  {
     let mut vec = Vec::new();
 
-    while let Ok(item) = list.try_iter() {
-      vec.push(pyobj_to_json_value(&item)?);
+    for item in obj.try_iter()? {
+      vec.push(pyobj_to_json_value(&item?)?);
     }
 
     Ok(JsonValue::Array(vec))
@@ -58,3 +72,103 @@ pub fn pydict_to_json_value(py_dict: &Bound<pyo3::types::PyDict>) -> PyResult<Js
 
   Ok(serde_json::Value::Object(map))
 }
+
+/// convert json value to pyobject
+pub fn json_value_to_pyobj(py: Python<'_>, value: &JsonValue) -> PyResult<PyObject> {
+  match value {
+    JsonValue::Null => Ok(py.None()),
+    JsonValue::Bool(val) => val.into_py_any(py),
+    JsonValue::Number(num) => {
+      if let Some(val) = num.as_i64() {
+        val.into_py_any(py)
+      } else if let Some(val) = num.as_u64() {
+        val.into_py_any(py)
+      } else {
+        num.as_f64().unwrap_or_default().into_py_any(py)
+      }
+    }
+    JsonValue::String(val) => val.into_py_any(py),
+    JsonValue::Array(items) => {
+      let list = PyList::empty(py);
+
+      for item in items {
+        list.append(json_value_to_pyobj(py, item)?)?;
+      }
+
+      list.into_py_any(py)
+    }
+    JsonValue::Object(map) => {
+      let dict = PyDict::new(py);
+
+      for (k, v) in map {
+        dict.set_item(k, json_value_to_pyobj(py, v)?)?;
+      }
+
+      dict.into_py_any(py)
+    }
+  }
+}
+
+/// serialize bytes as base64 text.
+pub mod base64_bytes {
+  use base64::{engine::general_purpose::STANDARD, Engine};
+  use serde::{Deserialize, Deserializer, Serializer};
+
+  /// encode the bytes as base64.
+  pub fn serialize<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&STANDARD.encode(bytes))
+  }
+
+  /// decode the bytes from base64.
+  pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+    let text = String::deserialize(deserializer)?;
+
+    STANDARD.decode(text).map_err(serde::de::Error::custom)
+  }
+}
+
+/// serialize optional bytes as base64 text.
+pub mod base64_bytes_option {
+  use base64::{engine::general_purpose::STANDARD, Engine};
+  use bytes::Bytes;
+  use serde::{Deserialize, Deserializer, Serializer};
+
+  /// encode the bytes as base64.
+  pub fn serialize<S: Serializer>(bytes: &Option<Bytes>, serializer: S) -> Result<S::Ok, S::Error> {
+    match bytes {
+      Some(bytes) => serializer.serialize_some(&STANDARD.encode(bytes)),
+      _ => serializer.serialize_none(),
+    }
+  }
+
+  /// decode the bytes from base64.
+  pub fn deserialize<'de, D: Deserializer<'de>>(
+    deserializer: D,
+  ) -> Result<Option<Bytes>, D::Error> {
+    Option::<String>::deserialize(deserializer)?
+      .map(|text| {
+        STANDARD
+          .decode(text)
+          .map(Bytes::from)
+          .map_err(serde::de::Error::custom)
+      })
+      .transpose()
+  }
+}
+
+/// get the bytes of a python bytes object or base64 text.
+pub fn pyobj_to_bytes(obj: &Bound<PyAny>) -> PyResult<Vec<u8>> {
+  use base64::{engine::general_purpose::STANDARD, Engine};
+
+  if let Ok(bytes) = obj.downcast::<PyBytes>() {
+    Ok(bytes.as_bytes().to_vec())
+  } else if let Ok(text) = obj.extract::<&str>() {
+    STANDARD
+      .decode(text)
+      .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+  } else {
+    Err(pyo3::exceptions::PyTypeError::new_err(
+      "Expected bytes or base64 text",
+    ))
+  }
+}
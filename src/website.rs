@@ -1,12 +1,27 @@
-use crate::{new_page, pydict_to_json_value, NPage, BUFFER};
+use crate::ai::AIUsage;
+use crate::callback::{CallbackErrors, ErrorPolicy};
+use crate::cron::Cron;
+use crate::extraction::ExtractionSchema;
+use crate::filter::PageFilter;
+use crate::handle::CrawlHandle;
+use crate::npage::PageOptions;
+use crate::receiver::{subscribe, LagPolicy};
+use crate::report::ReportTask;
+use crate::transform::ReturnFormat;
+use crate::{new_page, pydict_to_json_value, CrawlReport, NPage, PageIter, PageStream, BUFFER};
 use indexmap::IndexMap;
 use pyo3::prelude::*;
 use pyo3::types::PyDict;
 use spider::compact_str::CompactString;
 use spider::configuration::{WaitForDelay, WaitForIdleNetwork, WaitForSelector};
-use spider::tokio::select;
+use spider::tokio::sync::mpsc::{channel, Receiver};
+use spider::tokio::sync::oneshot;
+use spider::tokio::sync::watch;
 use spider::tokio::task::JoinHandle;
+use spider::tokio::{join, select};
 use spider::utils::shutdown;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
 /// A website holding the inner spider::website::Website from Rust fit for python.
@@ -17,11 +32,23 @@ pub struct Website {
   /// spawned subscription handles.
   subscription_handles: IndexMap<u32, JoinHandle<()>>,
   /// spawned crawl handles.
-  crawl_handles: IndexMap<u32, JoinHandle<()>>,
-  /// do not convert content to UT8.
-  raw_content: bool,
+  crawl_handles: IndexMap<u32, CrawlHandle>,
+  /// the id of the next subscription or crawl handle. Ids are never re-used.
+  next_handle_id: u32,
+  /// the options used to build the pages sent to python.
+  page_options: PageOptions,
   /// is the crawl running in the background.
   running_in_background: bool, // /// the file handle for storing data
+  /// the policy and exceptions raised from page callbacks.
+  callback_errors: CallbackErrors,
+  /// the report of the last crawl completed.
+  last_report: Arc<Mutex<Option<CrawlReport>>>,
+  /// the OpenAI tokens used across the crawls.
+  ai_usage: Arc<Mutex<AIUsage>>,
+  /// the default lag policy with the pages dropped across subscribers.
+  lag_policy: LagPolicy,
+  /// the lag policy of each subscription.
+  subscription_lag: IndexMap<u32, LagPolicy>,
 }
 
 #[pymethods]
@@ -34,8 +61,14 @@ impl Website {
       inner: spider::website::Website::new(&url),
       subscription_handles: IndexMap::new(),
       crawl_handles: IndexMap::new(),
-      raw_content: raw_content.unwrap_or_default(),
+      next_handle_id: 0,
+      page_options: PageOptions::new(raw_content.unwrap_or_default()),
       running_in_background: false, // file_handle: None,
+      callback_errors: CallbackErrors::default(),
+      last_report: Arc::new(Mutex::new(None)),
+      ai_usage: Arc::new(Mutex::new(AIUsage::default())),
+      lag_policy: LagPolicy::default(),
+      subscription_lag: IndexMap::new(),
     }
   }
 
@@ -44,32 +77,93 @@ impl Website {
     self.inner.get_status().to_string()
   }
 
-  /// subscribe and add an event listener.
-  pub fn subscribe(mut slf: PyRefMut<'_, Self>, on_page_event: PyObject) -> u32 {
-    let mut rx2 = slf
-      .inner
-      .subscribe(*BUFFER / 2)
-      .expect("sync feature should be enabled");
-    let raw_content = slf.raw_content;
+  /// get the report of the last crawl completed. Background crawls set this once they finish.
+  #[getter]
+  pub fn last_report(&self) -> Option<CrawlReport> {
+    match self.last_report.lock() {
+      Ok(report) => report.clone(),
+      _ => None,
+    }
+  }
+
+  /// get the OpenAI tokens used across the crawls completed.
+  #[getter]
+  pub fn ai_usage(&self) -> AIUsage {
+    match self.ai_usage.lock() {
+      Ok(usage) => *usage,
+      _ => AIUsage::default(),
+    }
+  }
+
+  /// get the handles of the crawls running in the background.
+  pub fn active_handles(&self) -> Vec<CrawlHandle> {
+    self
+      .crawl_handles
+      .values()
+      .filter(|handle| handle.is_running())
+      .cloned()
+      .collect()
+  }
+
+  /// subscribe and add an event listener. The buffer size adds a dedicated queue for the subscriber and
+  /// keep_on_lag keeps the subscription receiving after pages are dropped from falling behind.
+  /// The status_codes, url_regex, content_types, and min_bytes filters run before the page is sent to python.
+  #[allow(clippy::too_many_arguments)]
+  #[pyo3(signature = (on_page_event, buffer_size=None, keep_on_lag=None, status_codes=None, url_regex=None, content_types=None, min_bytes=None))]
+  pub fn subscribe(
+    mut slf: PyRefMut<'_, Self>,
+    on_page_event: PyObject,
+    buffer_size: Option<usize>,
+    keep_on_lag: Option<bool>,
+    status_codes: Option<Vec<u16>>,
+    url_regex: Option<String>,
+    content_types: Option<Vec<String>>,
+    min_bytes: Option<usize>,
+  ) -> PyResult<u32> {
+    let filter = PageFilter::new(status_codes, url_regex, content_types, min_bytes)?;
+    let lag = slf.lag_policy.subscriber(keep_on_lag);
+    let mut rx2 = subscribe(&mut slf.inner, lag.clone(), buffer_size);
+    let page_options = slf.page_options.clone();
+    let callback_errors = slf.callback_errors.clone();
+    let domain_name = slf.inner.get_url().inner().clone();
 
     let handle = pyo3_async_runtimes::tokio::get_runtime().spawn(async move {
-      while let Ok(res) = rx2.recv().await {
-        let page = new_page(&res, raw_content);
-        Python::with_gil(|py| {
-          let _ = on_page_event.call(py, (page, 0), None);
+      while let Some(res) = rx2.recv().await {
+        if !filter.matches(&res) {
+          continue;
+        }
+
+        let page = new_page(&res, &page_options);
+        let proceed = Python::with_gil(|py| {
+          callback_errors.handle(py, on_page_event.call(py, (page, 0), None))
         });
+
+        if !proceed {
+          shutdown(&domain_name).await;
+          break;
+        }
       }
     });
 
-    // always return the highest value as the next id.
-    let id = match slf.subscription_handles.last() {
-      Some(handle) => handle.0 + 1,
-      _ => 0,
-    };
+    let id = slf.next_id();
 
     slf.subscription_handles.insert(id, handle);
+    slf.subscription_lag.insert(id, lag);
 
-    id
+    Ok(id)
+  }
+
+  /// get the amount of pages dropped from subscribers falling behind the crawl. Pass the subscription id to get the
+  /// pages dropped for a single subscription.
+  #[pyo3(signature = (id=None))]
+  pub fn dropped_pages(&self, id: Option<u32>) -> u64 {
+    match id {
+      Some(id) => match self.subscription_lag.get(&id) {
+        Some(lag) => lag.dropped_pages(),
+        _ => 0,
+      },
+      _ => self.lag_policy.total_dropped_pages(),
+    }
   }
 
   /// remove a subscription listener.
@@ -83,6 +177,7 @@ impl Website {
           Some(h) => {
             h.abort();
             self.subscription_handles.swap_remove_entry(&id);
+            self.subscription_lag.swap_remove_entry(&id);
             true
           }
           _ => false,
@@ -94,6 +189,7 @@ impl Website {
         for k in self.subscription_handles.drain(..) {
           k.1.abort();
         }
+        self.subscription_lag.clear();
         keys > 0
       }
     }
@@ -122,7 +218,7 @@ impl Website {
 
         match handle {
           Some(h) => {
-            h.abort();
+            h.cancel();
             slf.crawl_handles.swap_remove_entry(&id);
             true
           }
@@ -132,7 +228,7 @@ impl Website {
       _ => {
         let keys = slf.crawl_handles.len();
         for k in slf.crawl_handles.drain(..) {
-          k.1.abort();
+          k.1.cancel();
         }
         keys > 0
       }
@@ -146,121 +242,20 @@ impl Website {
     on_page_event: Option<PyObject>,
     background: Option<bool>,
     headless: Option<bool>,
-  ) {
+  ) -> PyResult<CrawlOutcome> {
     // only run in background if on_page_event is handled for streaming.
-    let background = background.is_some() && background.unwrap_or_default();
-    let headless = headless.is_some() && headless.unwrap_or_default();
-    let raw_content = slf.raw_content;
+    let background = background.unwrap_or_default();
+    let headless = headless.unwrap_or_default();
 
     if background {
-      slf.running_in_background = background;
+      let handle = slf.spawn_crawl(CrawlMode::Crawl, on_page_event, headless);
+      Ok(CrawlOutcome::Handle(handle))
+    } else {
+      let py = slf.py();
+      slf
+        .run_crawl(py, CrawlMode::Crawl, on_page_event, headless)
+        .map(CrawlOutcome::Report)
     }
-
-    match on_page_event {
-      Some(callback) => {
-        if background {
-          let mut website = slf.inner.clone();
-          let mut rx2 = website
-            .subscribe(*BUFFER / 2)
-            .expect("sync feature should be enabled");
-          let rt = pyo3_async_runtimes::tokio::get_runtime();
-
-          let handle = rt.spawn(async move {
-            while let Ok(res) = rx2.recv().await {
-              let page = new_page(&res, raw_content);
-              Python::with_gil(|py| {
-                let _ = callback.call(py, (page,), None);
-              });
-            }
-          });
-
-          let crawl_id = match slf.crawl_handles.last() {
-            Some(handle) => handle.0 + 1,
-            _ => 0,
-          };
-
-          let crawl_handle = rt.spawn(async move {
-            if headless {
-              website.crawl().await;
-            } else {
-              website.crawl_raw().await;
-            }
-          });
-
-          let id = match slf.subscription_handles.last() {
-            Some(handle) => handle.0 + 1,
-            _ => 0,
-          };
-
-          slf.crawl_handles.insert(crawl_id, crawl_handle);
-          slf.subscription_handles.insert(id, handle);
-        } else {
-          let mut rx2 = slf
-            .inner
-            .subscribe(*BUFFER / 2)
-            .expect("sync feature should be enabled");
-
-          let py: Python<'_> = slf.py();
-          let rt = pyo3_async_runtimes::tokio::get_runtime();
-
-          let f1 = async {
-            while let Ok(res) = rx2.recv().await {
-              let page = new_page(&res, raw_content);
-              let _ = callback.call(py, (page,), None);
-            }
-          };
-
-          let f2 = async {
-            if headless {
-              slf.inner.crawl().await;
-            } else {
-              slf.inner.crawl_raw().await;
-            }
-          };
-
-          rt.block_on(async move {
-            select! {
-              _ = f1 => {
-                  // println!("Sync receiver droped");
-              }
-              _ = f2 => {
-                  // println!("operation completed");
-              }
-            }
-          });
-        }
-      }
-      _ => {
-        if background {
-          let mut website = slf.inner.clone();
-
-          let crawl_id = match slf.crawl_handles.last() {
-            Some(handle) => handle.0 + 1,
-            _ => 0,
-          };
-          let rt = pyo3_async_runtimes::tokio::get_runtime();
-
-          let crawl_handle = rt.spawn(async move {
-            if headless {
-              website.crawl().await;
-            } else {
-              website.crawl_raw().await;
-            }
-          });
-
-          slf.crawl_handles.insert(crawl_id, crawl_handle);
-        } else {
-          let _ = pyo3_async_runtimes::tokio::get_runtime().block_on(async move {
-            if headless {
-              slf.inner.crawl().await;
-            } else {
-              slf.inner.crawl_raw().await;
-            }
-            Ok::<(), ()>(())
-          });
-        }
-      }
-    };
   }
 
   /// crawl a website smart mode.
@@ -269,104 +264,19 @@ impl Website {
     mut slf: PyRefMut<'_, Self>,
     on_page_event: Option<PyObject>,
     background: Option<bool>,
-  ) {
+  ) -> PyResult<CrawlOutcome> {
     // only run in background if on_page_event is handled for streaming.
-    let background = background.is_some() && background.unwrap_or_default();
-    let raw_content = slf.raw_content;
+    let background = background.unwrap_or_default();
 
     if background {
-      slf.running_in_background = background;
+      let handle = slf.spawn_crawl(CrawlMode::Smart, on_page_event, false);
+      Ok(CrawlOutcome::Handle(handle))
+    } else {
+      let py = slf.py();
+      slf
+        .run_crawl(py, CrawlMode::Smart, on_page_event, false)
+        .map(CrawlOutcome::Report)
     }
-
-    match on_page_event {
-      Some(callback) => {
-        if background {
-          let mut website = slf.inner.clone();
-          let mut rx2 = website
-            .subscribe(*BUFFER / 2)
-            .expect("sync feature should be enabled");
-          let rt = pyo3_async_runtimes::tokio::get_runtime();
-
-          let handle = rt.spawn(async move {
-            while let Ok(res) = rx2.recv().await {
-              let page = new_page(&res, raw_content);
-              Python::with_gil(|py| {
-                let _ = callback.call(py, (page,), None);
-              });
-            }
-          });
-
-          let crawl_id = match slf.crawl_handles.last() {
-            Some(handle) => handle.0 + 1,
-            _ => 0,
-          };
-
-          let crawl_handle = rt.spawn(async move {
-            website.crawl_smart().await;
-          });
-
-          let id = match slf.subscription_handles.last() {
-            Some(handle) => handle.0 + 1,
-            _ => 0,
-          };
-
-          slf.crawl_handles.insert(crawl_id, crawl_handle);
-          slf.subscription_handles.insert(id, handle);
-        } else {
-          let mut rx2 = slf
-            .inner
-            .subscribe(*BUFFER / 2)
-            .expect("sync feature should be enabled");
-
-          let py: Python<'_> = slf.py();
-          let rt = pyo3_async_runtimes::tokio::get_runtime();
-
-          let f1 = async {
-            while let Ok(res) = rx2.recv().await {
-              let page = new_page(&res, raw_content);
-              let _ = callback.call(py, (page,), None);
-            }
-          };
-
-          let f2 = async {
-            slf.inner.crawl_smart().await;
-          };
-
-          rt.block_on(async move {
-            select! {
-              _ = f1 => {
-                  // println!("Sync receiver droped");
-              }
-              _ = f2 => {
-                  // println!("operation completed");
-              }
-            }
-          });
-        }
-      }
-      _ => {
-        if background {
-          let mut website = slf.inner.clone();
-          let rt = pyo3_async_runtimes::tokio::get_runtime();
-
-          let crawl_id = match slf.crawl_handles.last() {
-            Some(handle) => handle.0 + 1,
-            _ => 0,
-          };
-
-          let crawl_handle = rt.spawn(async move {
-            website.crawl_smart().await;
-          });
-
-          slf.crawl_handles.insert(crawl_id, crawl_handle);
-        } else {
-          let _ = pyo3_async_runtimes::tokio::get_runtime().block_on(async move {
-            slf.inner.crawl_smart().await;
-            Ok::<(), ()>(())
-          });
-        }
-      }
-    };
   }
 
   /// scrape a website holding onto the bytes stored until the end of the crawl.
@@ -376,141 +286,122 @@ impl Website {
     on_page_event: Option<PyObject>,
     background: Option<bool>,
     headless: Option<bool>,
-  ) {
-    let headless = headless.is_some() && headless.unwrap_or_default();
-    let raw_content = slf.raw_content;
-    let background = background.is_some() && background.unwrap_or_default();
+  ) -> PyResult<CrawlOutcome> {
+    let background = background.unwrap_or_default();
+    let headless = headless.unwrap_or_default();
 
     if background {
-      slf.running_in_background = background;
+      let handle = slf.spawn_crawl(CrawlMode::Scrape, on_page_event, headless);
+      Ok(CrawlOutcome::Handle(handle))
+    } else {
+      let py = slf.py();
+      slf
+        .run_crawl(py, CrawlMode::Scrape, on_page_event, headless)
+        .map(CrawlOutcome::Report)
     }
+  }
 
-    match on_page_event {
-      Some(callback) => {
-        if background {
-          let mut website = slf.inner.clone();
-          let mut rx2 = website
-            .subscribe(*BUFFER / 2)
-            .expect("sync feature should be enabled");
-          let rt = pyo3_async_runtimes::tokio::get_runtime();
-
-          let handle = rt.spawn(async move {
-            while let Ok(res) = rx2.recv().await {
-              let page = new_page(&res, raw_content);
-
-              // todo: remove global lock page events
-              Python::with_gil(|py| {
-                let _ = callback.call(py, (page,), None);
-              });
-            }
-          });
-
-          let crawl_id = match slf.crawl_handles.last() {
-            Some(handle) => handle.0 + 1,
-            _ => 0,
-          };
-
-          let crawl_handle = rt.spawn(async move {
-            if headless {
-              website.scrape().await;
-            } else {
-              website.scrape_raw().await;
-            }
-          });
-
-          let id = match slf.subscription_handles.last() {
-            Some(handle) => handle.0 + 1,
-            _ => 0,
-          };
-
-          slf.crawl_handles.insert(crawl_id, crawl_handle);
-          slf.subscription_handles.insert(id, handle);
-        } else {
-          let mut rx2 = slf
-            .inner
-            .subscribe(*BUFFER / 2)
-            .expect("sync feature should be enabled");
-
-          let py: Python<'_> = slf.py();
-          let rt = pyo3_async_runtimes::tokio::get_runtime();
+  /// crawl a website returning an awaitable that resolves when the crawl completes.
+  #[pyo3(signature = (on_page_event=None, background=None, headless=None))]
+  pub fn crawl_async<'py>(
+    slf: PyRefMut<'py, Self>,
+    on_page_event: Option<PyObject>,
+    background: Option<bool>,
+    headless: Option<bool>,
+  ) -> PyResult<Bound<'py, PyAny>> {
+    Self::run_crawl_async(
+      slf,
+      CrawlMode::Crawl,
+      on_page_event,
+      background.unwrap_or_default(),
+      headless.unwrap_or_default(),
+    )
+  }
 
-          let f1 = async {
-            while let Ok(res) = rx2.recv().await {
-              let page = new_page(&res, raw_content);
-              let _ = callback.call(py, (page,), None);
-            }
-          };
+  /// crawl a website smart mode returning an awaitable that resolves when the crawl completes.
+  #[pyo3(signature = (on_page_event=None, background=None))]
+  pub fn crawl_smart_async<'py>(
+    slf: PyRefMut<'py, Self>,
+    on_page_event: Option<PyObject>,
+    background: Option<bool>,
+  ) -> PyResult<Bound<'py, PyAny>> {
+    Self::run_crawl_async(
+      slf,
+      CrawlMode::Smart,
+      on_page_event,
+      background.unwrap_or_default(),
+      false,
+    )
+  }
 
-          let f2 = async {
-            if headless {
-              slf.inner.scrape().await;
-            } else {
-              slf.inner.scrape_raw().await;
-            }
-          };
+  /// scrape a website returning an awaitable that resolves when the scrape completes.
+  #[pyo3(signature = (on_page_event=None, background=None, headless=None))]
+  pub fn scrape_async<'py>(
+    slf: PyRefMut<'py, Self>,
+    on_page_event: Option<PyObject>,
+    background: Option<bool>,
+    headless: Option<bool>,
+  ) -> PyResult<Bound<'py, PyAny>> {
+    Self::run_crawl_async(
+      slf,
+      CrawlMode::Scrape,
+      on_page_event,
+      background.unwrap_or_default(),
+      headless.unwrap_or_default(),
+    )
+  }
 
-          rt.block_on(async move {
-            select! {
-              _ = f1 => {
-                  // println!("Sync receiver droped");
-              }
-              _ = f2 => {
-                  // println!("operation completed");
-              }
-            }
-          });
-        }
-      }
-      _ => {
-        if background {
-          let mut website = slf.inner.clone();
-          let rt = pyo3_async_runtimes::tokio::get_runtime();
+  /// start the crawl in the background returning an async iterator of the pages found.
+  #[pyo3(signature = (mode=None, headless=None))]
+  pub fn stream(
+    mut slf: PyRefMut<'_, Self>,
+    mode: Option<String>,
+    headless: Option<bool>,
+  ) -> PyResult<PageStream> {
+    let mode = CrawlMode::parse(mode.as_deref())?;
+    let (receiver, crawl_handle) = slf.spawn_page_channel(mode, headless.unwrap_or_default());
 
-          let crawl_id = match slf.crawl_handles.last() {
-            Some(handle) => handle.0 + 1,
-            _ => 0,
-          };
+    Ok(PageStream::new(receiver, crawl_handle))
+  }
 
-          let crawl_handle = rt.spawn(async move {
-            if headless {
-              website.scrape().await;
-            } else {
-              website.scrape_raw().await;
-            }
-          });
+  /// start the crawl in the background returning a blocking iterator of the pages found.
+  #[pyo3(signature = (mode=None, headless=None))]
+  pub fn iter_pages(
+    mut slf: PyRefMut<'_, Self>,
+    mode: Option<String>,
+    headless: Option<bool>,
+  ) -> PyResult<PageIter> {
+    let mode = CrawlMode::parse(mode.as_deref())?;
+    let (receiver, crawl_handle) = slf.spawn_page_channel(mode, headless.unwrap_or_default());
 
-          slf.crawl_handles.insert(crawl_id, crawl_handle);
-        } else {
-          let _ = pyo3_async_runtimes::tokio::get_runtime().block_on(async move {
-            if headless {
-              slf.inner.scrape().await;
-            } else {
-              slf.inner.scrape_raw().await;
-            }
-            Ok::<(), ()>(())
-          });
-        }
-      }
-    }
+    Ok(PageIter::new(receiver, crawl_handle))
   }
 
   /// run a cron job.
   #[pyo3(signature = (on_page_event=None))]
-  pub fn run_cron(mut slf: PyRefMut<'_, Self>, on_page_event: Option<PyObject>) -> Cron {
+  pub fn run_cron(mut slf: PyRefMut<'_, Self>, on_page_event: Option<PyObject>) -> PyResult<Cron> {
     let cron_handle = match on_page_event {
       Some(callback) => {
-        let mut rx2 = slf
-          .inner
-          .subscribe(*BUFFER / 2)
-          .expect("sync feature should be enabled");
-        let raw_content = slf.raw_content;
+        let lag = slf.lag_policy.subscriber(None);
+        let mut rx2 = subscribe(&mut slf.inner, lag, None);
+        let page_options = slf.page_options.clone();
+        let callback_errors = slf.callback_errors.clone();
+        let domain_name = slf.inner.get_url().inner().clone();
         let rt = pyo3_async_runtimes::tokio::get_runtime();
 
         let handler = rt.spawn(async move {
-          while let Ok(res) = rx2.recv().await {
-            Python::with_gil(|py| {
-              let _ = callback.call(py, (new_page(&res, raw_content),), None);
+          while let Some(res) = rx2.recv().await {
+            let proceed = Python::with_gil(|py| {
+              callback_errors.handle(
+                py,
+                callback.call(py, (new_page(&res, &page_options),), None),
+              )
             });
+
+            if !proceed {
+              shutdown(&domain_name).await;
+              break;
+            }
           }
         });
 
@@ -519,14 +410,12 @@ impl Website {
       _ => None,
     };
 
-    let inner = pyo3_async_runtimes::tokio::get_runtime()
-      .block_on(async move {
-        let runner: spider::async_job::Runner = slf.inner.run_cron().await;
-        Ok::<spider::async_job::Runner, ()>(runner)
-      })
-      .unwrap();
-
-    Cron { inner, cron_handle }
+    Cron::spawn(
+      slf.inner.clone(),
+      cron_handle,
+      slf.ai_usage.clone(),
+      slf.page_options.clone(),
+    )
   }
 
   /// get all the links of a website
@@ -568,11 +457,11 @@ impl Website {
   /// get all the pages of a website - requires calling website.scrape
   pub fn get_pages(&self) -> Vec<NPage> {
     let mut pages: Vec<NPage> = Vec::new();
-    let raw_content = self.raw_content;
+    let page_options = &self.page_options;
 
     if let Some(p) = self.inner.get_pages() {
       for page in p.iter() {
-        pages.push(new_page(page, raw_content));
+        pages.push(new_page(page, page_options));
       }
     }
 
@@ -595,6 +484,35 @@ impl Website {
   /// clear all links and page data
   pub fn clear(&mut self) {
     self.inner.clear();
+    self.page_options.graph.clear();
+    self.page_options.outcomes.reset();
+  }
+
+  /// Set how exceptions raised by page callbacks are handled: ignore, log, collect, or raise.
+  /// The raise policy stops the crawl and raises the first exception from the crawl call.
+  pub fn with_callback_error_policy(
+    mut slf: PyRefMut<'_, Self>,
+    policy: String,
+  ) -> PyResult<PyRefMut<'_, Self>> {
+    slf.callback_errors.policy = ErrorPolicy::parse(&policy)?;
+    Ok(slf)
+  }
+
+  /// get the exceptions raised by page callbacks using the collect or raise policy.
+  #[getter]
+  pub fn callback_errors(&self, py: Python<'_>) -> Vec<PyObject> {
+    self.callback_errors.to_list(py)
+  }
+
+  /// clear the exceptions raised by page callbacks.
+  pub fn clear_callback_errors(&self) {
+    self.callback_errors.clear();
+  }
+
+  /// Keep the page callbacks receiving after pages are dropped from falling behind the crawl. Defaults to ending the callbacks.
+  pub fn with_keep_on_lag(mut slf: PyRefMut<'_, Self>, keep_on_lag: bool) -> PyRefMut<'_, Self> {
+    slf.lag_policy.keep_on_lag = keep_on_lag;
+    slf
   }
 
   /// Set HTTP headers for request using [reqwest::header::HeaderMap](https://docs.rs/reqwest/latest/reqwest/header/struct.HeaderMap.html).
@@ -670,12 +588,14 @@ impl Website {
   /// Include subdomains detection.
   pub fn with_subdomains(mut slf: PyRefMut<'_, Self>, subdomains: bool) -> PyRefMut<'_, Self> {
     slf.inner.configuration.with_subdomains(subdomains);
+    slf.page_options.link_scope.subdomains = subdomains;
     slf
   }
 
   /// Include tld detection.
   pub fn with_tld(mut slf: PyRefMut<'_, Self>, tld: bool) -> PyRefMut<'_, Self> {
     slf.inner.configuration.with_tld(tld);
+    slf.page_options.link_scope.tld = tld;
     slf
   }
 
@@ -877,6 +797,62 @@ impl Website {
     slf
   }
 
+  /// Extract fields from each page with css selectors. The schema maps the field names to a selector or a dict with the
+  /// selector, the attr to read, multiple to return every match, and nested fields evaluated inside each match.
+  #[pyo3(signature = (schema=None))]
+  pub fn with_extraction<'a>(
+    mut slf: PyRefMut<'a, Self>,
+    schema: Option<&Bound<'a, PyDict>>,
+  ) -> PyResult<PyRefMut<'a, Self>> {
+    slf.page_options.extraction = match schema {
+      Some(py_obj) => {
+        let schema = pydict_to_json_value(py_obj)?;
+        Some(Arc::new(ExtractionSchema::parse(&schema)?))
+      }
+      _ => None,
+    };
+
+    Ok(slf)
+  }
+
+  /// Set the format of the page content: html, raw, markdown, or text. Markdown and text drop the scripts, styles, and navigation. The bytes kept with the raw_content set on the website are still sent.
+  #[pyo3(signature = (return_format=None))]
+  pub fn with_return_format(
+    mut slf: PyRefMut<'_, Self>,
+    return_format: Option<String>,
+  ) -> PyResult<PyRefMut<'_, Self>> {
+    let return_format = match return_format {
+      Some(format) => ReturnFormat::parse(&format)?,
+      _ => ReturnFormat::default(),
+    };
+
+    slf.page_options.return_format = return_format;
+
+    Ok(slf)
+  }
+
+  /// Include the links of each page with the anchor text, rel values, source element, internal or external, and if the crawler enqueued or skipped the link.
+  /// Set with_return_page_links to true to know the links skipped by robots.txt, the blacklist, or the budget.
+  pub fn with_rich_links(mut slf: PyRefMut<'_, Self>, rich_links: bool) -> PyRefMut<'_, Self> {
+    slf.page_options.rich_links = rich_links;
+    slf
+  }
+
+  /// List the images, scripts, stylesheets, fonts, media, and iframes referenced by each page as absolute urls.
+  pub fn with_page_resources(mut slf: PyRefMut<'_, Self>, resources: bool) -> PyRefMut<'_, Self> {
+    slf.page_options.resources = resources;
+    slf
+  }
+
+  /// Extract the schema.org data of each page from the JSON-LD blocks, microdata items, and RDFa properties.
+  pub fn with_structured_data(
+    mut slf: PyRefMut<'_, Self>,
+    structured_data: bool,
+  ) -> PyRefMut<'_, Self> {
+    slf.page_options.structured_data = structured_data;
+    slf
+  }
+
   /// Regex blacklist urls from the crawl
   #[pyo3(signature = (blacklist_url=None))]
   pub fn with_blacklist_url(
@@ -1014,26 +990,387 @@ impl Website {
   }
 }
 
-/// a runner for handling crons
-#[pyclass]
-pub struct Cron {
-  /// the runner task
-  inner: spider::async_job::Runner,
-  /// inner cron handle
-  cron_handle: Option<JoinHandle<()>>,
-}
+impl Website {
+  /// the next id to use for a subscription or crawl handle.
+  fn next_id(&mut self) -> u32 {
+    let id = self.next_handle_id;
+    self.next_handle_id = self.next_handle_id.wrapping_add(1);
+    id
+  }
 
-#[pymethods]
-impl Cron {
-  /// stop the cron instance
-  pub fn stop(mut slf: PyRefMut<'_, Self>) {
-    match &slf.cron_handle {
-      Some(h) => h.abort(),
-      _ => (),
+  /// spawn the crawl task on the shared runtime tracking it with a handle.
+  fn track_crawl<F>(&mut self, crawl: F) -> CrawlHandle
+  where
+    F: Future<Output = Option<CrawlReport>> + Send + 'static,
+  {
+    let (done_tx, done_rx) = watch::channel(false);
+    let report = Arc::new(Mutex::new(None));
+    let last_report = self.last_report.clone();
+    let ai_usage = self.ai_usage.clone();
+    let handle_report = report.clone();
+
+    let crawl_handle = pyo3_async_runtimes::tokio::get_runtime().spawn(async move {
+      if let Some(report) = crawl.await {
+        store_report(&handle_report, report.clone());
+        add_ai_usage(&ai_usage, &report);
+        store_report(&last_report, report);
+      }
+      let _ = done_tx.send(true);
+    });
+
+    // forget the crawls completed so the handles do not grow with each run.
+    self.crawl_handles.retain(|_, handle| handle.is_running());
+
+    let crawl_id = self.next_id();
+    let handle = CrawlHandle::new(
+      crawl_id,
+      crawl_handle.abort_handle(),
+      done_rx,
+      report,
+      self.callback_errors.clone(),
+    );
+
+    self.crawl_handles.insert(crawl_id, handle.clone());
+
+    handle
+  }
+
+  /// spawn the crawl on the shared runtime returning right away.
+  fn spawn_crawl(
+    &mut self,
+    mode: CrawlMode,
+    on_page_event: Option<PyObject>,
+    headless: bool,
+  ) -> CrawlHandle {
+    let mut website = self.inner.clone();
+    let page_options = self.page_options.clone();
+    let rt = pyo3_async_runtimes::tokio::get_runtime();
+
+    self.running_in_background = true;
+
+    let lag = self.lag_policy.subscriber(None);
+    let rx2 = if on_page_event.is_some() {
+      Some(subscribe(&mut website, lag.clone(), None))
+    } else {
+      None
     };
-    let _ = pyo3_async_runtimes::tokio::get_runtime().block_on(async move {
-      slf.inner.stop().await;
-      Ok::<(), ()>(())
+
+    let options = page_options.clone();
+    let crawl_handle = self.track_crawl(async move {
+      Some(mode.run_with_report(&mut website, headless, &options).await)
+    });
+
+    if let (Some(callback), Some(mut rx2)) = (on_page_event, rx2) {
+      let callback_errors = self.callback_errors.clone();
+      let crawl_abort = crawl_handle.clone();
+
+      let handle = rt.spawn(async move {
+        while let Some(res) = rx2.recv().await {
+          let page = new_page(&res, &page_options);
+
+          // todo: remove global lock page events
+          let proceed =
+            Python::with_gil(|py| callback_errors.handle(py, callback.call(py, (page,), None)));
+
+          if !proceed {
+            crawl_abort.cancel();
+            break;
+          }
+        }
+      });
+
+      let id = self.next_id();
+      self.subscription_handles.insert(id, handle);
+      self.subscription_lag.insert(id, lag);
+    }
+
+    crawl_handle
+  }
+
+  /// spawn the crawl on the shared runtime forwarding the pages found into a channel.
+  /// The crawl runs to completion while the channel applies backpressure to the forwarder. Dropping the stream cancels the crawl with its handle.
+  fn spawn_page_channel(
+    &mut self,
+    mode: CrawlMode,
+    headless: bool,
+  ) -> (Receiver<NPage>, CrawlHandle) {
+    let mut website = self.inner.clone();
+    let page_options = self.page_options.clone();
+    // keep receiving after lagging so a slow consumer never ends the stream early.
+    let mut rx2 = subscribe(&mut website, self.lag_policy.subscriber(Some(true)), None);
+    let (tx, rx) = channel(*BUFFER);
+    let (done_tx, mut done_rx) = oneshot::channel::<()>();
+
+    self.running_in_background = true;
+
+    let crawl_handle = self.track_crawl(async move {
+      let crawl = async {
+        let report = mode
+          .run_with_report(&mut website, headless, &page_options)
+          .await;
+        let _ = done_tx.send(());
+        report
+      };
+
+      let forward = async {
+        loop {
+          select! {
+            biased;
+            res = rx2.recv() => match res {
+              Some(res) => {
+                if tx.send(new_page(&res, &page_options)).await.is_err() {
+                  return;
+                }
+              }
+              _ => return,
+            },
+            _ = &mut done_rx => break,
+          }
+        }
+
+        // deliver the pages still buffered after the crawl completes.
+        while let Some(res) = rx2.try_recv() {
+          if tx.send(new_page(&res, &page_options)).await.is_err() {
+            break;
+          }
+        }
+      };
+
+      let (report, _) = join!(crawl, forward);
+
+      Some(report)
     });
+
+    (rx, crawl_handle)
+  }
+
+  /// run the crawl blocking until it completes. The GIL is released while crawling.
+  fn run_crawl(
+    &mut self,
+    py: Python<'_>,
+    mode: CrawlMode,
+    on_page_event: Option<PyObject>,
+    headless: bool,
+  ) -> PyResult<CrawlReport> {
+    let rt = pyo3_async_runtimes::tokio::get_runtime();
+    let callback_errors = self.callback_errors.clone();
+    let errors_start = callback_errors.len();
+    let report_task = ReportTask::spawn(&mut self.inner, &self.page_options);
+
+    match on_page_event {
+      Some(callback) => {
+        let mut rx2 = subscribe(&mut self.inner, self.lag_policy.subscriber(None), None);
+        let page_options = self.page_options.clone();
+        let inner = &mut self.inner;
+        let callback_errors = &callback_errors;
+
+        py.allow_threads(|| {
+          let f1 = async {
+            while let Some(res) = rx2.recv().await {
+              let page = new_page(&res, &page_options);
+
+              // only hold the GIL to deliver the page.
+              let proceed =
+                Python::with_gil(|py| callback_errors.handle(py, callback.call(py, (page,), None)));
+
+              if !proceed {
+                break;
+              }
+            }
+          };
+
+          let f2 = mode.run(inner, headless);
+
+          rt.block_on(async move {
+            select! {
+              _ = f1 => {
+                  // println!("Sync receiver droped");
+              }
+              _ = f2 => {
+                  // println!("operation completed");
+              }
+            }
+          });
+        });
+      }
+      _ => {
+        let inner = &mut self.inner;
+        py.allow_threads(|| rt.block_on(mode.run(inner, headless)));
+      }
+    }
+
+    let inner = &self.inner;
+    let report = py.allow_threads(|| rt.block_on(report_task.finish(inner)));
+
+    add_ai_usage(&self.ai_usage, &report);
+    store_report(&self.last_report, report.clone());
+    callback_errors.raised_since(py, errors_start)?;
+
+    Ok(report)
+  }
+
+  /// run the crawl as a python awaitable storing the results on the website once complete.
+  fn run_crawl_async<'py>(
+    mut slf: PyRefMut<'py, Self>,
+    mode: CrawlMode,
+    on_page_event: Option<PyObject>,
+    background: bool,
+    headless: bool,
+  ) -> PyResult<Bound<'py, PyAny>> {
+    let py = slf.py();
+
+    if background {
+      let handle = slf.spawn_crawl(mode, on_page_event, headless);
+      return pyo3_async_runtimes::tokio::future_into_py(py, async move {
+        Ok(CrawlOutcome::Handle(handle))
+      });
+    }
+
+    let mut website = slf.inner.clone();
+    let page_options = slf.page_options.clone();
+    let callback_errors = slf.callback_errors.clone();
+    let errors_start = callback_errors.len();
+    let rx2 = if on_page_event.is_some() {
+      Some(subscribe(
+        &mut website,
+        slf.lag_policy.subscriber(None),
+        None,
+      ))
+    } else {
+      None
+    };
+    let report_task = ReportTask::spawn(&mut website, &slf.page_options);
+    let last_report = slf.last_report.clone();
+    let ai_usage = slf.ai_usage.clone();
+    let slf_ref: Py<Self> = slf.into();
+
+    pyo3_async_runtimes::tokio::future_into_py(py, async move {
+      match (on_page_event, rx2) {
+        (Some(callback), Some(mut rx2)) => {
+          let f1 = async {
+            while let Some(res) = rx2.recv().await {
+              let page = new_page(&res, &page_options);
+              let proceed =
+                Python::with_gil(|py| callback_errors.handle(py, callback.call(py, (page,), None)));
+
+              if !proceed {
+                break;
+              }
+            }
+          };
+
+          let f2 = mode.run(&mut website, headless);
+
+          select! {
+            _ = f1 => (),
+            _ = f2 => ()
+          }
+        }
+        _ => {
+          mode.run(&mut website, headless).await;
+        }
+      }
+
+      let report = report_task.finish(&website).await;
+      add_ai_usage(&ai_usage, &report);
+      store_report(&last_report, report.clone());
+
+      // store the crawl state so get_links, get_pages, and size reflect the run.
+      Python::with_gil(|py| {
+        match slf_ref.try_borrow_mut(py) {
+          Ok(mut slf) => slf.inner = website,
+          _ => return Err(pyo3::exceptions::PyRuntimeError::new_err(
+            "The crawl completed while the website was in use and the results could not be stored",
+          )),
+        }
+
+        callback_errors.raised_since(py, errors_start)
+      })?;
+
+      Ok(CrawlOutcome::Report(report))
+    })
+  }
+}
+
+/// the result of a crawl run. Background runs return a handle to the crawl.
+#[derive(IntoPyObject)]
+pub enum CrawlOutcome {
+  /// the report of the crawl completed.
+  Report(CrawlReport),
+  /// the handle of the crawl running in the background.
+  Handle(CrawlHandle),
+}
+
+/// store the report of the crawl completed.
+fn store_report(last_report: &Mutex<Option<CrawlReport>>, report: CrawlReport) {
+  if let Ok(mut last_report) = last_report.lock() {
+    last_report.replace(report);
+  }
+}
+
+/// add the OpenAI tokens used during the crawl to the website total.
+pub(crate) fn add_ai_usage(ai_usage: &Mutex<AIUsage>, report: &CrawlReport) {
+  if let Ok(mut ai_usage) = ai_usage.lock() {
+    ai_usage.add(&report.ai_usage);
+  }
+}
+
+/// the type of crawl to perform.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CrawlMode {
+  /// gather the links of the website.
+  Crawl,
+  /// gather the links and hold the page resources.
+  Scrape,
+  /// crawl using http and upgrade to chrome when javascript is required.
+  Smart,
+}
+
+impl CrawlMode {
+  /// parse the crawl type from python defaulting to crawl.
+  pub fn parse(mode: Option<&str>) -> PyResult<Self> {
+    match mode.unwrap_or("crawl") {
+      "crawl" => Ok(CrawlMode::Crawl),
+      "scrape" => Ok(CrawlMode::Scrape),
+      "smart" => Ok(CrawlMode::Smart),
+      m => Err(pyo3::exceptions::PyValueError::new_err(format!(
+        "Unknown crawl mode {m}, expected crawl, scrape, or smart"
+      ))),
+    }
+  }
+
+  /// run the crawl type on the website.
+  pub async fn run(self, website: &mut spider::website::Website, headless: bool) {
+    match self {
+      CrawlMode::Crawl => {
+        if headless {
+          website.crawl().await;
+        } else {
+          website.crawl_raw().await;
+        }
+      }
+      CrawlMode::Scrape => {
+        if headless {
+          website.scrape().await;
+        } else {
+          website.scrape_raw().await;
+        }
+      }
+      CrawlMode::Smart => {
+        website.crawl_smart().await;
+      }
+    }
+  }
+
+  /// run the crawl type on the website returning the report of the run.
+  pub async fn run_with_report(
+    self,
+    website: &mut spider::website::Website,
+    headless: bool,
+    options: &PageOptions,
+  ) -> CrawlReport {
+    let report_task = ReportTask::spawn(website, options);
+    self.run(website, headless).await;
+    report_task.finish(website).await
   }
 }